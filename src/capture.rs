@@ -1,8 +1,15 @@
 //! Types and traits related to parameter capturing.
 //!
-//! Currently this module is a placeholder for new traits coming later; the
-//! only things stored in this module at this point are simple type aliases
-//! (which are extremely likely to change in future, so don't rely on them).
+//! Captures are represented by simple type aliases, which bind a parameter
+//! name to the bounds of a value within the routed path (these are extremely
+//! likely to change in future, so don't rely on them). The `Params` type can
+//! be used to resolve a set of captures into owned values where necessary.
+//!
+//! As the names of captures borrow from the router, the `OwnedCaptures` type
+//! is also provided for cases where captures need to outlive the router (such
+//! as being sent across tasks) without resolving every value up front.
+use std::ops::Range;
+use std::sync::Arc;
 
 /// Basic type alias for a captured value pair.
 pub type Capture<'a> = (&'a str, (usize, usize));
@@ -33,3 +40,170 @@ pub fn find_capture<'a, 'p>(path: &'p str, capt: CapturesRef<'a>, name: &str) ->
 pub fn lookup_capture<'a, 'p>(path: &'p str, capt: Capture<'a>) -> &'p str {
     &path[((capt.1).0)..((capt.1).1)]
 }
+
+/// An owned set of resolved parameter values.
+///
+/// Unlike `Captures`, this structure doesn't borrow from either the router or
+/// the routed path, so it can be freely stored or sent across threads (i.e. in
+/// the extensions of a HTTP request). Parameters retain the order in which they
+/// were captured, and names are not required to be unique.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Params {
+    inner: Vec<(String, String)>,
+}
+
+impl Params {
+    /// Creates a new, empty set of `Params`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new set of `Params` by resolving captures against a path.
+    ///
+    /// This function will panic if the bounds provided are invalid for the provided path,
+    /// although this should never happen in reality unless you're mocking captures.
+    pub fn from_captures<'a>(path: &str, capt: CapturesRef<'a>) -> Self {
+        let inner = capt
+            .iter()
+            .map(|capt| (capt.0.to_owned(), lookup_capture(path, *capt).to_owned()))
+            .collect();
+
+        Self { inner }
+    }
+
+    /// Retrieves a potential parameter value by name.
+    ///
+    /// If multiple values exist, only the first value will be found.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.inner
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Appends a parameter to the end of this set.
+    pub fn push<N: Into<String>, V: Into<String>>(&mut self, name: N, value: V) {
+        self.inner.push((name.into(), value.into()))
+    }
+
+    /// Retrieves an iterator over the name/value pairs in this set.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.inner.iter().map(|(n, v)| (n.as_str(), v.as_str()))
+    }
+
+    /// Retrieves the number of parameters in this set.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Determines whether this set contains any parameters.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+}
+
+/// An owned set of captures, which does not borrow from the router.
+///
+/// Names are stored as `Arc<str>` (so cloning a set is cheap, and names can be
+/// shared with the router via `Router::lookup_owned`) and values are stored as
+/// the byte range of the value within the routed path, just as with `Captures`.
+/// This means the path is still required to resolve each value; if the path
+/// will not be kept around, use `Params` instead.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct OwnedCaptures {
+    inner: Vec<(Arc<str>, Range<usize>)>,
+}
+
+impl OwnedCaptures {
+    /// Creates a new, empty set of `OwnedCaptures`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new set of `OwnedCaptures` from a set of borrowed captures.
+    ///
+    /// As borrowed captures only carry the names as string slices, this has to
+    /// allocate each name. When routing, `Router::lookup_owned` should be used
+    /// instead, as it reuses the names interned by the router.
+    pub fn from_captures<'a>(capt: CapturesRef<'a>) -> Self {
+        let inner = capt
+            .iter()
+            .map(|(name, (start, end))| (Arc::from(*name), *start..*end))
+            .collect();
+
+        Self { inner }
+    }
+
+    /// Retrieves the bounds of a potential captured value by name.
+    ///
+    /// If multiple values exist, only the first value will be found.
+    pub fn get(&self, name: &str) -> Option<Range<usize>> {
+        self.inner
+            .iter()
+            .find(|(n, _)| &**n == name)
+            .map(|(_, range)| range.clone())
+    }
+
+    /// Retrieves a potential captured value from a path by name.
+    ///
+    /// This function will panic if the bounds provided are invalid for the provided path,
+    /// although this should never happen in reality unless you're mocking captures.
+    pub fn find<'p>(&self, path: &'p str, name: &str) -> Option<&'p str> {
+        self.get(name).map(|range| &path[range])
+    }
+
+    /// Appends a capture to the end of this set.
+    pub fn push<N: Into<Arc<str>>>(&mut self, name: N, range: Range<usize>) {
+        self.inner.push((name.into(), range))
+    }
+
+    /// Retrieves an iterator over the name/bounds pairs in this set.
+    pub fn iter(&self) -> impl Iterator<Item = (&Arc<str>, Range<usize>)> {
+        self.inner.iter().map(|(n, range)| (n, range.clone()))
+    }
+
+    /// Converts this set back into the borrowed `Captures` form.
+    ///
+    /// This is useful for passing owned captures to functions which accept
+    /// `CapturesRef`, such as `find_capture`.
+    pub fn as_captures(&self) -> Captures<'_> {
+        self.inner
+            .iter()
+            .map(|(n, range)| (&**n, (range.start, range.end)))
+            .collect()
+    }
+
+    /// Resolves this set of captures against a path into a set of `Params`.
+    ///
+    /// This function will panic if the bounds provided are invalid for the provided path,
+    /// although this should never happen in reality unless you're mocking captures.
+    pub fn to_params(&self, path: &str) -> Params {
+        let mut params = Params::new();
+        for (name, range) in &self.inner {
+            params.push(&**name, &path[range.clone()]);
+        }
+        params
+    }
+
+    /// Retrieves the number of captures in this set.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Determines whether this set contains any captures.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+}
+
+impl<'a> From<CapturesRef<'a>> for OwnedCaptures {
+    fn from(capt: CapturesRef<'a>) -> Self {
+        Self::from_captures(capt)
+    }
+}
+
+impl<'a> From<Captures<'a>> for OwnedCaptures {
+    fn from(capt: Captures<'a>) -> Self {
+        Self::from_captures(&capt)
+    }
+}
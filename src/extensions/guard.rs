@@ -0,0 +1,215 @@
+//! Request guards used to select between handlers in a `HttpRouter`.
+//!
+//! A `Guard` is a predicate over a `RequestView`, which allows several handlers
+//! to be registered against the same method and path via `HttpRouter::guarded`.
+//! When routing a request, the guarded handlers of a method are checked in the
+//! order they were registered, and the first handler whose guard passes is used.
+//! If no guard passes, the unguarded handler of the method is used (if any):
+//!
+//! ```rust,ignore
+//! router.guarded(Method::GET, "/users", guard::accept("text/csv"), export);
+//! router.guarded(Method::GET, "/users", guard::header("x-api-version", "2"), list_v2);
+//! router.get("/users", list);
+//! ```
+//!
+//! As guards need access to the request, they're only taken into account when
+//! routing a request via `HttpRouter::route_request` (or `lookup_request`), and
+//! will be skipped when routing on a method and path alone. Each guard is also
+//! able to describe itself, so that routes can be listed via `HttpRouter::routes`.
+//!
+//! This module is available via the `"web"` Cargo feature.
+use http::{Extensions, HeaderMap, Method, Request, Uri};
+
+use std::fmt;
+
+/// A predicate used to select a handler based on a request.
+pub trait Guard: Send + Sync + 'static {
+    /// Checks whether the provided request passes this guard.
+    fn check(&self, req: &RequestView) -> bool;
+
+    /// Retrieves a description of this guard, for use in route listings.
+    fn describe(&self) -> String;
+}
+
+/// Implementation of `Guard` for boxed guards.
+impl Guard for Box<dyn Guard> {
+    fn check(&self, req: &RequestView) -> bool {
+        (**self).check(req)
+    }
+
+    fn describe(&self) -> String {
+        (**self).describe()
+    }
+}
+
+/// A borrowed view of a request, as provided to a `Guard`.
+///
+/// This provides access to everything in a request except the body, which is
+/// not available during routing. It can be created from any `Request` via the
+/// `From` implementation, regardless of the type of body.
+#[derive(Clone, Copy)]
+pub struct RequestView<'a> {
+    method: &'a Method,
+    uri: &'a Uri,
+    headers: &'a HeaderMap,
+    extensions: &'a Extensions,
+}
+
+impl<'a> RequestView<'a> {
+    /// Retrieves the method of the request.
+    pub fn method(&self) -> &'a Method {
+        self.method
+    }
+
+    /// Retrieves the URI of the request.
+    pub fn uri(&self) -> &'a Uri {
+        self.uri
+    }
+
+    /// Retrieves the headers of the request.
+    pub fn headers(&self) -> &'a HeaderMap {
+        self.headers
+    }
+
+    /// Retrieves the extensions of the request.
+    pub fn extensions(&self) -> &'a Extensions {
+        self.extensions
+    }
+
+    /// Retrieves the value of a header, if it's present and valid as a string.
+    ///
+    /// If a header is provided several times, only the first value is returned.
+    pub fn header(&self, name: &str) -> Option<&'a str> {
+        self.headers.get(name).and_then(|value| value.to_str().ok())
+    }
+
+    /// Retrieves the value of a query parameter.
+    ///
+    /// The query string is split on `&` and `=` without any decoding taking place,
+    /// so both the name and value are compared exactly as they appear in the URI.
+    pub fn query(&self, name: &str) -> Option<&'a str> {
+        self.uri.query()?.split('&').find_map(|pair| {
+            let mut parts = pair.splitn(2, '=');
+            match parts.next() {
+                Some(key) if key == name => Some(parts.next().unwrap_or_default()),
+                _ => None,
+            }
+        })
+    }
+}
+
+impl<'a, B> From<&'a Request<B>> for RequestView<'a> {
+    fn from(req: &'a Request<B>) -> Self {
+        Self {
+            method: req.method(),
+            uri: req.uri(),
+            headers: req.headers(),
+            extensions: req.extensions(),
+        }
+    }
+}
+
+impl<'a> fmt::Debug for RequestView<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RequestView")
+            .field("method", self.method)
+            .field("uri", self.uri)
+            .field("headers", self.headers)
+            .finish()
+    }
+}
+
+/// Creates a guard which requires a header to have an exact value.
+pub fn header(name: &str, value: &str) -> Box<dyn Guard> {
+    let name = name.to_ascii_lowercase();
+    let value = value.to_owned();
+    from_fn(format!("header({}: {})", name, value), move |req| {
+        req.header(&name) == Some(value.as_str())
+    })
+}
+
+/// Creates a guard which requires the `Accept` header to contain a media type.
+///
+/// The header is split into its comma separated media types, and any parameters
+/// (such as quality values) are ignored when comparing against the media type.
+pub fn accept(media_type: &str) -> Box<dyn Guard> {
+    let media_type = media_type.to_owned();
+    from_fn(format!("accept({})", media_type), move |req| {
+        req.header("accept").is_some_and(|accept| {
+            accept
+                .split(',')
+                .any(|value| essence(value).eq_ignore_ascii_case(&media_type))
+        })
+    })
+}
+
+/// Creates a guard which requires the `Content-Type` header to be a media type.
+///
+/// Any parameters of the header (such as a charset) are ignored when comparing
+/// against the provided media type.
+pub fn content_type(media_type: &str) -> Box<dyn Guard> {
+    let media_type = media_type.to_owned();
+    from_fn(format!("content_type({})", media_type), move |req| {
+        req.header("content-type")
+            .is_some_and(|value| essence(value).eq_ignore_ascii_case(&media_type))
+    })
+}
+
+/// Creates a guard which requires a query parameter to have an exact value.
+///
+/// See `RequestView::query` for details on how query parameters are matched.
+pub fn query(name: &str, value: &str) -> Box<dyn Guard> {
+    let name = name.to_owned();
+    let value = value.to_owned();
+    from_fn(format!("query({}={})", name, value), move |req| {
+        req.query(&name) == Some(value.as_str())
+    })
+}
+
+/// Creates a guard which requires all of the provided guards to pass.
+pub fn all(guards: Vec<Box<dyn Guard>>) -> Box<dyn Guard> {
+    let description = guards
+        .iter()
+        .map(|guard| guard.describe())
+        .collect::<Vec<_>>()
+        .join(" && ");
+    from_fn(description, move |req| {
+        guards.iter().all(|guard| guard.check(req))
+    })
+}
+
+/// Creates a guard from a description and a custom predicate.
+pub fn from_fn<D, F>(description: D, f: F) -> Box<dyn Guard>
+where
+    D: Into<String>,
+    F: Fn(&RequestView) -> bool + Send + Sync + 'static,
+{
+    Box::new(FnGuard {
+        description: description.into(),
+        f,
+    })
+}
+
+/// Guard type used to wrap a predicate function.
+struct FnGuard<F> {
+    description: String,
+    f: F,
+}
+
+impl<F> Guard for FnGuard<F>
+where
+    F: Fn(&RequestView) -> bool + Send + Sync + 'static,
+{
+    fn check(&self, req: &RequestView) -> bool {
+        (self.f)(req)
+    }
+
+    fn describe(&self) -> String {
+        self.description.clone()
+    }
+}
+
+/// Retrieves the essence of a media type, without any parameters.
+fn essence(media_type: &str) -> &str {
+    media_type.split(';').next().unwrap_or_default().trim()
+}
@@ -5,25 +5,147 @@
 //! typical insertion functions, this router exposes HTTP verbs as the
 //! names of functions to provide a simple API for mapping HTTP requests.
 //!
+//! Alongside the `HttpRouter`, this module also exposes a `HostRouter` which
+//! can be used to select a `HttpRouter` based on the host of a request. This
+//! enables serving several virtual hosts (or tenants) from a single process.
+//!
+//! Requests can also be routed directly via `HttpRouter::route_request`, which
+//! stores the captured `Params` and the `MatchedRoute` in the extensions of the
+//! request. These values can then be accessed via the `RequestExt` trait, so
+//! handlers are able to access parameters from the request alone.
+//!
+//! Several handlers can be registered against the same method and path when
+//! they're guarded by a `Guard`; see the `guard` module for further details.
+//!
+//! Paths can be normalized before routing via `HttpRouter::normalize`, which
+//! guarantees that captured values contain no traversal components (such as
+//! `..`); see the `normalize` module for further details.
+//!
 //! To activate this extension, use the `"web"` Cargo feature.
-use http::Method;
+use http::header::HOST;
+#[cfg(any(feature = "hyper", feature = "tower"))]
+use http::{
+    header::{HeaderValue, ALLOW},
+    Response, StatusCode,
+};
+use http::{Method, Request};
 
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
 
-use crate::capture::Captures;
+use crate::capture::{Captures, Params};
+use crate::guard::{Guard, RequestView};
+use crate::matcher::Matcher;
+use crate::normalize::{NormalizeError, Normalizer};
 use crate::parser::Parser;
-use crate::router::Router;
+use crate::pattern::RoutePattern;
+use crate::router::{Conflict, LimitError, Limits, MountError, Router};
 
 /// A basic HTTP routing structure for generic handlers.
 ///
 /// Almost all internals of this router are controlled by the usual `Router`,
 /// with this structure simply providing a more HTTP friendly API for ergonomics.
 ///
-/// To construct a router this way, HTTP verbs must be used as there must be a
-/// verb associated with each request. There is currently no way to match any
-/// verb, although this will potentially be improved at some point in future.
+/// To construct a router this way, HTTP verbs are typically used to register a
+/// handler against each method of a path. Handlers which wish to deal with any
+/// method can be registered via `any`, and a router-wide fallback handler can be
+/// provided via `fallback` to deal with requests which match no route at all.
+///
+/// The router can also opt into automatic handling of `HEAD` and `OPTIONS`
+/// requests via `auto_head` and `auto_options` respectively, to avoid having
+/// to register the same handlers against every path in the router.
+#[derive(Clone)]
 pub struct HttpRouter<T> {
-    router: Router<HashMap<Method, T>>,
+    router: Router<Endpoint<T>>,
+    fallback: Option<T>,
+    auto_head: bool,
+    auto_options: bool,
+    normalizer: Option<Normalizer>,
+}
+
+/// A route registered in a `HttpRouter`, as returned by `HttpRouter::routes`.
+pub struct Route<'a, T> {
+    path: RoutePattern,
+    matchers: Vec<&'a dyn Matcher>,
+    method: Option<&'a Method>,
+    guard: Option<&'a dyn Guard>,
+    handler: &'a T,
+}
+
+impl<'a, T> Route<'a, T> {
+    /// Retrieves the path pattern of the route.
+    pub fn path(&self) -> &RoutePattern {
+        &self.path
+    }
+
+    /// Retrieves the method of the route, or `None` for a handler of any method.
+    pub fn method(&self) -> Option<&'a Method> {
+        self.method
+    }
+
+    /// Retrieves the guard of the route, if the handler is guarded.
+    pub fn guard(&self) -> Option<&'a dyn Guard> {
+        self.guard
+    }
+
+    /// Retrieves the handler of the route.
+    pub fn handler(&self) -> &'a T {
+        self.handler
+    }
+
+    /// Retrieves the matchers of each segment of the route, in path order.
+    pub fn matchers(&self) -> &[&'a dyn Matcher] {
+        &self.matchers
+    }
+}
+
+/// The kind of handler selected when routing via `HttpRouter::resolve`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Matched {
+    /// A handler registered against the exact request method.
+    Method,
+    /// A handler registered against any method via `HttpRouter::any`.
+    Any,
+    /// A `GET` handler used for a `HEAD` request via `HttpRouter::auto_head`.
+    ///
+    /// In this case the caller should drop the body of the response.
+    Head,
+    /// The router-wide fallback handler.
+    Fallback,
+}
+
+/// The result of routing a method/path combination via `HttpRouter::lookup`.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Lookup<'a, T> {
+    /// A handler was found, along with any captures found during matching.
+    Found(&'a T, Captures<'a>),
+    /// A `GET` handler was found for a `HEAD` request via `HttpRouter::auto_head`.
+    ///
+    /// The handler should be invoked as usual, but the body of the response
+    /// should be dropped before being sent back to the caller.
+    Head(&'a T, Captures<'a>),
+    /// An `OPTIONS` request should be answered via `HttpRouter::auto_options`.
+    ///
+    /// This contains the methods which are available on the path (or across the
+    /// entire router in the case of `OPTIONS *`), and can be used to populate the
+    /// `Allow` header of the response.
+    Options(Vec<Method>),
+    /// The path exists, but has no handler for the method.
+    ///
+    /// This contains the methods which are available on the path, and can be
+    /// used to populate the `Allow` header of a `405 Method Not Allowed`.
+    MethodNotAllowed(Vec<Method>),
+    /// The path does not exist.
+    NotFound,
+    /// The path was rejected by the `Normalizer` or the `Limits` of the router.
+    ///
+    /// Normalization only applies to `HttpRouter::route_request`, and only when
+    /// paths are being normalized via `HttpRouter::normalize`. Limits apply to
+    /// every lookup once they're set via `HttpRouter::limits`, and hosts which
+    /// exceed the limits set via `HostRouter::limits` are also rejected this way.
+    InvalidPath,
 }
 
 /// Delegates a HTTP method to the `route` method in a router.
@@ -33,8 +155,13 @@ macro_rules! http_delegate {
         #[doc = "Registers a handler for the `"]
         #[doc = $smethod]
         #[doc = "` HTTP method."]
-        pub fn $name(&mut self, path: &str, t: T) {
-            self.insert($method, path, t)
+        #[doc = ""]
+        #[doc = "Panics if the path exceeds the depth limit of the router; see `try_route`."]
+        pub fn $name<P>(&mut self, path: P, t: T)
+        where
+            P: AsRef<str>,
+        {
+            self.route($method, path, t)
         }
     };
 }
@@ -44,6 +171,10 @@ impl<T> HttpRouter<T> {
     pub fn new(parsers: Vec<Box<dyn Parser>>) -> Self {
         Self {
             router: Router::new(parsers),
+            fallback: None,
+            auto_head: false,
+            auto_options: false,
+            normalizer: None,
         }
     }
 
@@ -58,29 +189,1166 @@ impl<T> HttpRouter<T> {
     http_delegate!(put, Method::PUT, "PUT");
     http_delegate!(trace, Method::TRACE, "TRACE");
 
-    /// Inserts a route/handler pair for the provided method and path.
-    fn insert(&mut self, method: Method, path: &str, t: T) {
-        self.router.update(path, |node| {
-            let mut map = node.unwrap_or_default();
-            if !map.contains_key(&method) {
-                map.reserve(1);
+    /// Registers a handler for the provided HTTP method.
+    ///
+    /// This can be used with any method, including extension methods such as
+    /// those used by WebDAV (i.e. `PROPFIND`), which can be created using the
+    /// `Method::from_bytes` function. Extension methods are routed exactly the
+    /// same as any of the standard methods.
+    ///
+    /// As with all registration methods, the path can be provided as either a
+    /// string or a parsed `RoutePattern`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the path exceeds the depth limit of the router; see `try_route`.
+    pub fn route<P>(&mut self, method: Method, path: P, t: T)
+    where
+        P: AsRef<str>,
+    {
+        registered(self.try_route(method, path, t))
+    }
+
+    /// Attempts to register a handler for the provided HTTP method.
+    ///
+    /// This is the same as `route`, except that a path exceeding the depth limit
+    /// of the router results in a `LimitError` rather than a panic.
+    pub fn try_route<P>(&mut self, method: Method, path: P, t: T) -> Result<(), LimitError>
+    where
+        P: AsRef<str>,
+    {
+        self.router.try_update(path, |node| {
+            let mut endpoint = node.unwrap_or_default();
+            let handlers = endpoint.methods.entry(method).or_default();
+            handlers.unguarded.replace(t);
+            endpoint
+        })
+    }
+
+    /// Registers a guarded handler for the provided HTTP method.
+    ///
+    /// Any number of guarded handlers can be registered against a method, and
+    /// they will be checked in the order they were registered. If no guard passes,
+    /// the handler registered via `route` (or any of the method delegates) will
+    /// be used instead. Guards are only checked when routing a request, via either
+    /// `lookup_request` or `route_request`; see the `guard` module for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the path exceeds the depth limit of the router; see `try_guarded`.
+    pub fn guarded<P, G>(&mut self, method: Method, path: P, guard: G, t: T)
+    where
+        P: AsRef<str>,
+        G: Guard,
+    {
+        registered(self.try_guarded(method, path, guard, t))
+    }
+
+    /// Attempts to register a guarded handler for the provided HTTP method.
+    ///
+    /// This is the same as `guarded`, except that a path exceeding the depth limit
+    /// of the router results in a `LimitError` rather than a panic.
+    pub fn try_guarded<P, G>(
+        &mut self,
+        method: Method,
+        path: P,
+        guard: G,
+        t: T,
+    ) -> Result<(), LimitError>
+    where
+        P: AsRef<str>,
+        G: Guard,
+    {
+        self.router.try_update(path, |node| {
+            let mut endpoint = node.unwrap_or_default();
+            let handlers = endpoint.methods.entry(method).or_default();
+            handlers.guarded.push((Arc::new(guard), t));
+            endpoint
+        })
+    }
+
+    /// Registers a handler for each of the provided HTTP methods.
+    ///
+    /// The handler is cloned for each method after the first, so this is
+    /// best used with handlers which are cheap to clone (such as an `Arc`).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the path exceeds the depth limit of the router; see `try_route_many`.
+    pub fn route_many<I, P>(&mut self, methods: I, path: P, t: T)
+    where
+        I: IntoIterator<Item = Method>,
+        P: AsRef<str>,
+        T: Clone,
+    {
+        registered(self.try_route_many(methods, path, t))
+    }
+
+    /// Attempts to register a handler for each of the provided HTTP methods.
+    ///
+    /// This is the same as `route_many`, except that a path exceeding the depth
+    /// limit of the router results in a `LimitError` rather than a panic.
+    pub fn try_route_many<I, P>(&mut self, methods: I, path: P, t: T) -> Result<(), LimitError>
+    where
+        I: IntoIterator<Item = Method>,
+        P: AsRef<str>,
+        T: Clone,
+    {
+        self.router.try_update(path, |node| {
+            let mut endpoint = node.unwrap_or_default();
+            for method in methods {
+                let handlers = endpoint.methods.entry(method).or_default();
+                handlers.unguarded.replace(t.clone());
+            }
+            endpoint
+        })
+    }
+
+    /// Registers a handler for any HTTP method.
+    ///
+    /// This handler is used for any method which does not have a handler
+    /// registered against it explicitly, so it's possible to mix handlers
+    /// for specific methods with a handler for any other method.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the path exceeds the depth limit of the router; see `try_any`.
+    pub fn any<P>(&mut self, path: P, t: T)
+    where
+        P: AsRef<str>,
+    {
+        registered(self.try_any(path, t))
+    }
+
+    /// Attempts to register a handler for any HTTP method.
+    ///
+    /// This is the same as `any`, except that a path exceeding the depth limit
+    /// of the router results in a `LimitError` rather than a panic.
+    pub fn try_any<P>(&mut self, path: P, t: T) -> Result<(), LimitError>
+    where
+        P: AsRef<str>,
+    {
+        self.router.try_update(path, |node| {
+            let mut endpoint = node.unwrap_or_default();
+            endpoint.any.replace(t);
+            endpoint
+        })
+    }
+
+    /// Registers a fallback handler for requests which match no route.
+    ///
+    /// When routing via `handler` or `resolve`, the fallback handler is used
+    /// whenever no handler is found for a method and path combination, regardless
+    /// of whether the path itself exists. The `lookup` methods never return the
+    /// fallback handler, as they report a path which exists without a handler for
+    /// the method as `Lookup::MethodNotAllowed`. Services built on `lookup` (such
+    /// as those of the tower and hyper integrations) respond with a `405` in this
+    /// case, and only use the fallback handler for `Lookup::NotFound`.
+    pub fn fallback(&mut self, t: T) {
+        self.fallback.replace(t);
+    }
+
+    /// Retrieves the fallback handler, if one has been registered.
+    pub fn fallback_handler(&self) -> Option<&T> {
+        self.fallback.as_ref()
+    }
+
+    /// Enables or disables automatic handling of `HEAD` requests.
+    ///
+    /// When enabled, a `HEAD` request for a path without a `HEAD` handler will
+    /// be routed to the `GET` handler of the path instead (if there is one). As
+    /// the response to a `HEAD` request must not have a body, this is signalled
+    /// to the caller via `Lookup::Head` or `Matched::Head` so it can be dropped.
+    pub fn auto_head(&mut self, enabled: bool) {
+        self.auto_head = enabled;
+    }
+
+    /// Enables or disables automatic handling of `OPTIONS` requests.
+    ///
+    /// When enabled, an `OPTIONS` request for a path without an `OPTIONS` handler
+    /// (or a handler for any method) will result in a `Lookup::Options` containing
+    /// the methods available on the path. An `OPTIONS *` request will result in the
+    /// union of all methods available across the router.
+    pub fn auto_options(&mut self, enabled: bool) {
+        self.auto_options = enabled;
+    }
+
+    /// Enables normalization of request paths via the provided `Normalizer`.
+    ///
+    /// When enabled, paths are normalized by `route_request` before routing, so
+    /// any `Params` stored in a request are free of traversal components. Paths
+    /// rejected during normalization result in a `Lookup::InvalidPath`. Note that
+    /// `lookup` and `lookup_request` always route the path exactly as provided.
+    pub fn normalize(&mut self, normalizer: Normalizer) {
+        self.normalizer.replace(normalizer);
+    }
+
+    /// Sets the resource limits of the inner router.
+    ///
+    /// See `Router::limits` for details. Paths exceeding the limits result in a
+    /// `Lookup::InvalidPath` when routed via `lookup` (and the related methods).
+    pub fn limits(&mut self, limits: Limits) {
+        self.router.limits(limits);
+    }
+
+    /// Converts this router into a router of another handler type.
+    ///
+    /// See `Router::map` for further details, as this simply maps every
+    /// handler registered against each method of the inner router.
+    pub fn map<U, F>(self, mut f: F) -> HttpRouter<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        HttpRouter {
+            router: self.router.map(|endpoint| endpoint.map(&mut f)),
+            fallback: self.fallback.map(f),
+            auto_head: self.auto_head,
+            auto_options: self.auto_options,
+            normalizer: self.normalizer,
+        }
+    }
+
+    /// Creates a router of another handler type from a reference to this router.
+    ///
+    /// See `Router::map_ref` for further details.
+    pub fn map_ref<U, F>(&self, mut f: F) -> HttpRouter<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        HttpRouter {
+            router: self.router.map_ref(|endpoint| endpoint.map_ref(&mut f)),
+            fallback: self.fallback.as_ref().map(f),
+            auto_head: self.auto_head,
+            auto_options: self.auto_options,
+            normalizer: self.normalizer.clone(),
+        }
+    }
+
+    /// Attempts to convert this router into a router of another handler type.
+    ///
+    /// See `Router::try_map` for further details.
+    pub fn try_map<U, E, F>(self, mut f: F) -> Result<HttpRouter<U>, E>
+    where
+        F: FnMut(T) -> Result<U, E>,
+    {
+        Ok(HttpRouter {
+            router: self.router.try_map(|endpoint| endpoint.try_map(&mut f))?,
+            fallback: self.fallback.map(f).transpose()?,
+            auto_head: self.auto_head,
+            auto_options: self.auto_options,
+            normalizer: self.normalizer,
+        })
+    }
+
+    /// Registers a group of routes underneath a shared path prefix.
+    ///
+    /// The provided function receives a `Scope` which exposes the same methods
+    /// for registration as the router itself, except that every path is joined
+    /// onto the provided prefix. Scopes can be nested to build deeper prefixes.
+    pub fn scope<F>(&mut self, prefix: &str, f: F)
+    where
+        F: FnOnce(&mut Scope<T>),
+    {
+        f(&mut Scope {
+            router: self,
+            prefix: prefix.to_owned(),
+            wrappers: Vec::new(),
+        })
+    }
+
+    /// Mounts the routes of another `HttpRouter` underneath a prefix of this router.
+    ///
+    /// See `Router::mount` for details on how the paths of both routers are merged.
+    /// Where both routers contain handlers for the same path, the handlers of each
+    /// method are merged; guarded handlers are checked after the existing guarded
+    /// handlers of the method, and only handlers registered without a guard (or
+    /// via `any`) against the same method are treated as a conflict. Conflicts are
+    /// resolved using the provided `Conflict` policy.
+    ///
+    /// Only the routes are mounted; the fallback handler, normalizer and other
+    /// settings of the provided router are discarded in favour of this router.
+    pub fn mount(
+        &mut self,
+        prefix: &str,
+        other: HttpRouter<T>,
+        conflict: Conflict,
+    ) -> Result<(), MountError> {
+        self.router.mount_with(
+            prefix,
+            other.router,
+            conflict,
+            Endpoint::conflicts,
+            |existing, incoming| existing.merge(incoming, conflict),
+        )
+    }
+
+    /// Retrieves every route registered in the router.
+    ///
+    /// Routes are grouped by path, and the routes of each path are ordered by
+    /// method name. The guarded handlers of each method are listed in the order
+    /// they're checked, followed by the unguarded handler and then any handler
+    /// registered for any method. The fallback handler is not included.
+    pub fn routes(&self) -> Vec<Route<'_, T>> {
+        let mut routes = Vec::new();
+
+        self.router.visit(|nodes, endpoint| {
+            let segments = nodes.iter().map(|node| node.segment()).collect();
+            let path = self.router.pattern(segments);
+            let matchers: Vec<&dyn Matcher> = nodes.iter().map(|node| node.matcher()).collect();
+
+            let mut methods: Vec<&Method> = endpoint.methods.keys().collect();
+            methods.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+            for method in methods {
+                let handlers = &endpoint.methods[method];
+                let guarded = handlers
+                    .guarded
+                    .iter()
+                    .map(|(guard, handler)| (Some(&**guard), handler));
+
+                for (guard, handler) in guarded.chain(handlers.unguarded.iter().map(|h| (None, h)))
+                {
+                    routes.push(Route {
+                        path: path.clone(),
+                        matchers: matchers.clone(),
+                        method: Some(method),
+                        guard,
+                        handler,
+                    });
+                }
+            }
+
+            if let Some(handler) = &endpoint.any {
+                routes.push(Route {
+                    path,
+                    matchers,
+                    method: None,
+                    guard: None,
+                    handler,
+                });
             }
-            map.insert(method, t);
-            map
         });
+
+        routes
     }
 
     /// Attempts to route a method/path combination to a handler.
     ///
     /// If a handler exists for the provided method/path combination, it will
-    /// be returned - along with any captures found during matching. If the
-    /// path does not exist, or the method is not available on the path, a
-    /// `None` value will be returned and a handler will not be found.
-    pub fn handler<'a>(&'a self, method: &Method, path: &str) -> Option<(&T, Captures<'a>)> {
-        // look for the node in the router based on the path
-        self.router.lookup(path).and_then(|(node, captures)| {
+    /// be returned - along with any captures found during matching. This will
+    /// also take into account any handlers registered via `any`, as well as the
+    /// fallback handler of the router. If none of these handlers are available,
+    /// a `None` value will be returned and a handler will not be found.
+    ///
+    /// Paths exceeding the limits of the router are rejected outright, so `None`
+    /// is returned for them without falling back to the fallback handler.
+    ///
+    /// See `resolve` if you need to know which of these handlers was selected.
+    #[inline]
+    pub fn handler<'a>(&'a self, method: &Method, path: &str) -> Option<(&'a T, Captures<'a>)> {
+        self.resolve(method, path)
+            .map(|(handler, captures, _)| (handler, captures))
+    }
+
+    /// Attempts to route a method/path combination to a handler.
+    ///
+    /// Unlike `handler`, this distinguishes between a path which does not exist
+    /// and a path which exists without a handler for the provided method. In the
+    /// latter case, the methods available on the path are returned so that they
+    /// can be provided to the caller (i.e. via an `Allow` header).
+    ///
+    /// Handlers registered via `any` are taken into account, but the fallback
+    /// handler is not, as a fallback would otherwise hide each of these cases.
+    /// As there's no request available, guarded handlers are never selected.
+    #[inline]
+    pub fn lookup<'a>(&'a self, method: &Method, path: &str) -> Lookup<'a, T> {
+        self.lookup_with(method, path, None, None)
+    }
+
+    /// Attempts to route a HTTP request to a handler.
+    ///
+    /// This is the same as `lookup`, except that the method and path are taken
+    /// from the request, and any guarded handlers are checked against the request.
+    pub fn lookup_request<'a, B>(&'a self, req: &Request<B>) -> Lookup<'a, T> {
+        let view = RequestView::from(req);
+        self.lookup_with(req.method(), req.uri().path(), Some(&view), None)
+    }
+
+    /// Attempts to route a HTTP request to a handler.
+    ///
+    /// This is the same as `lookup_request`, except that when a handler is found,
+    /// the captures are resolved into an owned set of `Params` and stored in the
+    /// request extensions (alongside the `MatchedRoute`). These values can then
+    /// be retrieved from the request via the `RequestExt` trait.
+    ///
+    /// If paths are normalized via `normalize`, the normalized path is routed and
+    /// the bounds of the returned captures refer to the normalized path, rather
+    /// than the path of the request. The `Params` should be used in this case.
+    #[inline]
+    pub fn route_request<'a, B>(&'a self, req: &mut Request<B>) -> Lookup<'a, T> {
+        self.route_request_with(req, Params::new())
+    }
+
+    /// Routes a HTTP request, storing path captures after the provided `Params`.
+    fn route_request_with<'a, B>(
+        &'a self,
+        req: &mut Request<B>,
+        mut params: Params,
+    ) -> Lookup<'a, T> {
+        let path = match self.normalize_path(req.uri().path()) {
+            Ok(path) => path,
+            Err(_) => return Lookup::InvalidPath,
+        };
+
+        let view = RequestView::from(&*req);
+        let mut segments = Vec::new();
+        let lookup = self.lookup_with(req.method(), &path, Some(&view), Some(&mut segments));
+
+        if let Lookup::Found(_, captures) | Lookup::Head(_, captures) = &lookup {
+            for (name, value) in Params::from_captures(&path, captures).iter() {
+                params.push(name, value);
+            }
+            let route = MatchedRoute(self.router.pattern(segments));
+
+            let extensions = req.extensions_mut();
+            extensions.insert(params);
+            extensions.insert(route);
+        }
+
+        lookup
+    }
+
+    /// Attempts to route a method/path combination to a handler.
+    ///
+    /// This is the same as `handler`, except that the kind of handler selected
+    /// is also returned. Handlers are selected in order of precedence, starting
+    /// with a handler for the exact method (or a `GET` handler for a `HEAD` request
+    /// when `auto_head` is enabled), then a handler registered for any method, and
+    /// finally the fallback handler (which will have no captures).
+    #[inline]
+    pub fn resolve<'a>(
+        &'a self,
+        method: &Method,
+        path: &str,
+    ) -> Option<(&'a T, Captures<'a>, Matched)> {
+        self.resolve_with(method, path, None)
+    }
+
+    /// Normalizes a path via the `Normalizer` of this router, if there is one.
+    ///
+    /// The asterisk-form used by `OPTIONS *` requests is not a path, and so it's
+    /// never normalized (which would otherwise turn it into the path `/*`).
+    fn normalize_path<'p>(&self, path: &'p str) -> Result<Cow<'p, str>, NormalizeError> {
+        if path == "*" {
+            return Ok(Cow::Borrowed(path));
+        }
+
+        match &self.normalizer {
+            Some(normalizer) => normalizer.normalize(path),
+            None => Ok(Cow::Borrowed(path)),
+        }
+    }
+
+    /// Routes a method/path combination, checking guards against a request.
+    ///
+    /// If a vector of segments is provided, the segments of the nodes walked are
+    /// pushed into it so that the matched route can be formatted afterward.
+    fn lookup_with<'a>(
+        &'a self,
+        method: &Method,
+        path: &str,
+        req: Option<&RequestView>,
+        segments: Option<&mut Vec<&'a str>>,
+    ) -> Lookup<'a, T> {
+        let options = self.auto_options && method == Method::OPTIONS;
+
+        if options && path == "*" {
+            let mut allowed = Vec::new();
+            self.router.for_each(|endpoint| {
+                for method in self.allowed(endpoint, req) {
+                    if !allowed.contains(&method) {
+                        allowed.push(method);
+                    }
+                }
+            });
+            sort_methods(&mut allowed);
+            return Lookup::Options(allowed);
+        }
+
+        let found = match segments {
+            Some(segments) => self.router.try_lookup_segments(path, segments),
+            None => self.router.try_lookup(path),
+        };
+
+        let (node, captures) = match found {
+            Ok(Some(found)) => found,
+            Ok(None) => return Lookup::NotFound,
+            Err(_) => return Lookup::InvalidPath,
+        };
+
+        match self.select(node, method, req) {
+            Some((handler, Matched::Head)) => Lookup::Head(handler, captures),
+            Some((handler, _)) => Lookup::Found(handler, captures),
+            None if options => Lookup::Options(self.allowed(node, req)),
+            None => Lookup::MethodNotAllowed(self.allowed(node, req)),
+        }
+    }
+
+    /// Resolves a method/path combination, checking guards against a request.
+    fn resolve_with<'a>(
+        &'a self,
+        method: &Method,
+        path: &str,
+        req: Option<&RequestView>,
+    ) -> Option<(&'a T, Captures<'a>, Matched)> {
+        // look for the node in the router based on the path, where paths
+        // exceeding the limits of the router never reach the fallback
+        let found = self.router.try_lookup(path).ok()?;
+        let found = found.and_then(|(node, captures)| {
             // unpack the method and map the handler back directly
-            node.get(method).map(|handler| (handler, captures))
+            self.select(node, method, req)
+                .map(|(handler, matched)| (handler, captures, matched))
+        });
+
+        // fall back to the fallback handler, if any
+        found.or_else(|| {
+            self.fallback
+                .as_ref()
+                .map(|handler| (handler, Vec::new(), Matched::Fallback))
         })
     }
+
+    /// Retrieves the methods available on an endpoint, including automatic methods.
+    ///
+    /// A method is only available when it has a handler which could be selected
+    /// for the request; methods with only guarded handlers are excluded unless a
+    /// guard passes for the request (and so are always excluded without one).
+    fn allowed(&self, endpoint: &Endpoint<T>, req: Option<&RequestView>) -> Vec<Method> {
+        let mut allowed: Vec<Method> = endpoint
+            .methods
+            .iter()
+            .filter(|(_, handlers)| handlers.select(req).is_some())
+            .map(|(method, _)| method.clone())
+            .collect();
+
+        if self.auto_head && allowed.contains(&Method::GET) && !allowed.contains(&Method::HEAD) {
+            allowed.push(Method::HEAD);
+        }
+
+        if self.auto_options && !allowed.contains(&Method::OPTIONS) {
+            allowed.push(Method::OPTIONS);
+        }
+
+        sort_methods(&mut allowed);
+        allowed
+    }
+
+    /// Selects a handler from an endpoint based on the provided method.
+    fn select<'a>(
+        &self,
+        endpoint: &'a Endpoint<T>,
+        method: &Method,
+        req: Option<&RequestView>,
+    ) -> Option<(&'a T, Matched)> {
+        let handler = |method: &Method| {
+            endpoint
+                .methods
+                .get(method)
+                .and_then(|handlers| handlers.select(req))
+        };
+
+        if let Some(handler) = handler(method) {
+            return Some((handler, Matched::Method));
+        }
+
+        if self.auto_head && method == Method::HEAD {
+            if let Some(handler) = handler(&Method::GET) {
+                return Some((handler, Matched::Head));
+            }
+        }
+
+        endpoint.any.as_ref().map(|handler| (handler, Matched::Any))
+    }
+}
+
+/// Sorts a set of methods by name, to provide a stable order to callers.
+fn sort_methods(methods: &mut [Method]) {
+    methods.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+}
+
+/// Handlers registered against a single path of a `HttpRouter`.
+#[derive(Clone)]
+struct Endpoint<T> {
+    methods: HashMap<Method, Handlers<T>>,
+    any: Option<T>,
+}
+
+impl<T> Endpoint<T> {
+    /// Converts this endpoint into an endpoint of another handler type.
+    fn map<U, F>(self, f: &mut F) -> Endpoint<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        Endpoint {
+            methods: self
+                .methods
+                .into_iter()
+                .map(|(m, h)| (m, h.map(f)))
+                .collect(),
+            any: self.any.map(f),
+        }
+    }
+
+    /// Creates an endpoint of another handler type from a reference.
+    fn map_ref<U, F>(&self, f: &mut F) -> Endpoint<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Endpoint {
+            methods: self
+                .methods
+                .iter()
+                .map(|(m, h)| (m.clone(), h.map_ref(f)))
+                .collect(),
+            any: self.any.as_ref().map(f),
+        }
+    }
+
+    /// Attempts to convert this endpoint into an endpoint of another handler type.
+    fn try_map<U, E, F>(self, f: &mut F) -> Result<Endpoint<U>, E>
+    where
+        F: FnMut(T) -> Result<U, E>,
+    {
+        Ok(Endpoint {
+            methods: self
+                .methods
+                .into_iter()
+                .map(|(m, h)| h.try_map(f).map(|h| (m, h)))
+                .collect::<Result<_, _>>()?,
+            any: self.any.map(f).transpose()?,
+        })
+    }
+
+    /// Determines whether merging another endpoint into this one would conflict.
+    fn conflicts(&self, other: &Self) -> bool {
+        (self.any.is_some() && other.any.is_some())
+            || other.methods.iter().any(|(method, handlers)| {
+                self.methods
+                    .get(method)
+                    .is_some_and(|existing| existing.conflicts(handlers))
+            })
+    }
+
+    /// Merges another endpoint into this one, resolving conflicts via a policy.
+    fn merge(&mut self, other: Self, conflict: Conflict) {
+        for (method, handlers) in other.methods {
+            self.methods
+                .entry(method)
+                .or_default()
+                .merge(handlers, conflict);
+        }
+
+        merge_handler(&mut self.any, other.any, conflict);
+    }
+}
+
+impl<T> Default for Endpoint<T> {
+    fn default() -> Self {
+        Self {
+            methods: HashMap::new(),
+            any: None,
+        }
+    }
+}
+
+/// Handlers registered against a single method of an `Endpoint`.
+#[derive(Clone)]
+struct Handlers<T> {
+    guarded: Vec<(Arc<dyn Guard>, T)>,
+    unguarded: Option<T>,
+}
+
+impl<T> Handlers<T> {
+    /// Selects the first handler whose guard passes, or the unguarded handler.
+    fn select(&self, req: Option<&RequestView>) -> Option<&T> {
+        req.and_then(|req| {
+            self.guarded
+                .iter()
+                .find(|(guard, _)| guard.check(req))
+                .map(|(_, handler)| handler)
+        })
+        .or(self.unguarded.as_ref())
+    }
+
+    /// Converts these handlers into handlers of another type.
+    fn map<U, F>(self, f: &mut F) -> Handlers<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        Handlers {
+            guarded: self
+                .guarded
+                .into_iter()
+                .map(|(guard, t)| (guard, f(t)))
+                .collect(),
+            unguarded: self.unguarded.map(f),
+        }
+    }
+
+    /// Creates handlers of another type from a reference.
+    fn map_ref<U, F>(&self, f: &mut F) -> Handlers<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Handlers {
+            guarded: self
+                .guarded
+                .iter()
+                .map(|(guard, t)| (guard.clone(), f(t)))
+                .collect(),
+            unguarded: self.unguarded.as_ref().map(f),
+        }
+    }
+
+    /// Attempts to convert these handlers into handlers of another type.
+    fn try_map<U, E, F>(self, f: &mut F) -> Result<Handlers<U>, E>
+    where
+        F: FnMut(T) -> Result<U, E>,
+    {
+        Ok(Handlers {
+            guarded: self
+                .guarded
+                .into_iter()
+                .map(|(guard, t)| f(t).map(|t| (guard, t)))
+                .collect::<Result<_, _>>()?,
+            unguarded: self.unguarded.map(f).transpose()?,
+        })
+    }
+
+    /// Determines whether merging other handlers into these would conflict.
+    fn conflicts(&self, other: &Self) -> bool {
+        self.unguarded.is_some() && other.unguarded.is_some()
+    }
+
+    /// Merges other handlers into these, resolving conflicts via a policy.
+    fn merge(&mut self, other: Self, conflict: Conflict) {
+        self.guarded.extend(other.guarded);
+        merge_handler(&mut self.unguarded, other.unguarded, conflict);
+    }
+}
+
+impl<T> Default for Handlers<T> {
+    fn default() -> Self {
+        Self {
+            guarded: Vec::new(),
+            unguarded: None,
+        }
+    }
+}
+
+/// A HTTP routing structure which routes on host before routing on path.
+///
+/// Hosts are registered using patterns such as `api.example.com`, and support
+/// the same parsers as any other router (so `:tenant.example.com` will work with
+/// the `DynamicParser`). Internally hosts are stored in a `Router` delimited by
+/// `.` and walked in reverse, so that the labels are routed from the top level
+/// domain downward.
+///
+/// As the bounds of host captures refer to the host, whereas the bounds of path
+/// captures refer to the path, the two are returned as separate `Captures` when
+/// routing via `handler` or `request_handler`. When routing via `route_request`,
+/// the values of both are merged into the `Params` stored in the request, with
+/// host values placed ahead of path values. Hosts are matched exactly as given,
+/// so if you require case insensitive matching you should normalize the host
+/// before routing.
+pub struct HostRouter<T> {
+    hosts: Router<HttpRouter<T>>,
+    fallback: Option<HttpRouter<T>>,
+}
+
+impl<T> HostRouter<T> {
+    /// Creates a new `HostRouter` with provided matchers for host labels.
+    pub fn new(parsers: Vec<Box<dyn Parser>>) -> Self {
+        Self {
+            hosts: Router::with_delimiter(parsers, '.', true),
+            fallback: None,
+        }
+    }
+
+    /// Sets the resource limits applied to hosts during lookup and registration.
+    ///
+    /// These limits only apply to the routing of hosts (where each label of a
+    /// host is a segment); the limits applied to paths are set on each of the
+    /// `HttpRouter` values via `HttpRouter::limits`.
+    pub fn limits(&mut self, limits: Limits) {
+        self.hosts.limits(limits);
+    }
+
+    /// Registers a `HttpRouter` to use for hosts matching the provided pattern.
+    ///
+    /// If a router has already been registered against the pattern, it will be
+    /// replaced by the provided router.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pattern exceeds the depth limit of the router; see `try_host`.
+    pub fn host(&mut self, pattern: &str, router: HttpRouter<T>) {
+        self.hosts.insert(pattern, router)
+    }
+
+    /// Attempts to register a `HttpRouter` to use for hosts matching a pattern.
+    ///
+    /// This is the same as `host`, except that a pattern exceeding the depth limit
+    /// of the router results in a `LimitError` rather than a panic.
+    pub fn try_host(&mut self, pattern: &str, router: HttpRouter<T>) -> Result<(), LimitError> {
+        self.hosts.try_insert(pattern, router)
+    }
+
+    /// Registers a fallback `HttpRouter` to use for unknown hosts.
+    ///
+    /// The fallback router is only used when no registered host pattern matches
+    /// the incoming host. If a host matches but the path does not match in the
+    /// router for that host, the fallback router will not be used.
+    pub fn fallback(&mut self, router: HttpRouter<T>) {
+        self.fallback.replace(router);
+    }
+
+    /// Attempts to route a method/host/path combination to a handler.
+    ///
+    /// Any port provided in the host is ignored during routing. As the bounds of
+    /// captures refer to the value they were captured from, the host captures
+    /// and path captures are returned separately (in that order). Hosts which
+    /// exceed the limits of this router never reach the fallback router.
+    pub fn handler<'a>(
+        &'a self,
+        method: &Method,
+        host: &str,
+        path: &str,
+    ) -> Option<(&'a T, Captures<'a>, Captures<'a>)> {
+        let (router, captures) = self.router(strip_port(host)).ok()??;
+
+        router
+            .handler(method, path)
+            .map(|(handler, path_captures)| (handler, captures, path_captures))
+    }
+
+    /// Attempts to route a HTTP request to a handler, storing routing values.
+    ///
+    /// This is the same as `HttpRouter::route_request` on the router selected for
+    /// the host of the request, except that the host captures are also resolved
+    /// into the `Params` stored in the request extensions (ahead of any values
+    /// captured from the path). The captures returned in the `Lookup` only contain
+    /// those captured from the path; the `Params` should be used for host values.
+    pub fn route_request<'a, B>(&'a self, req: &mut Request<B>) -> Lookup<'a, T> {
+        let host = request_host(req).unwrap_or_default();
+
+        let (router, captures) = match self.router(host) {
+            Ok(Some(found)) => found,
+            Ok(None) => return Lookup::NotFound,
+            Err(_) => return Lookup::InvalidPath,
+        };
+
+        let params = Params::from_captures(host, &captures);
+        router.route_request_with(req, params)
+    }
+
+    /// Attempts to route a HTTP request to a handler.
+    ///
+    /// The host is taken from the `Host` header of the request, falling back to
+    /// the authority of the request URI in the case that there is no header set.
+    /// See `request_host` for details on how to access the routed host value.
+    pub fn request_handler<'a, B>(
+        &'a self,
+        req: &Request<B>,
+    ) -> Option<(&'a T, Captures<'a>, Captures<'a>)> {
+        let host = request_host(req).unwrap_or_default();
+        self.handler(req.method(), host, req.uri().path())
+    }
+
+    /// Selects the router for a host (without a port), along with host captures.
+    fn router<'a>(
+        &'a self,
+        host: &str,
+    ) -> Result<Option<(&'a HttpRouter<T>, Captures<'a>)>, LimitError> {
+        Ok(match self.hosts.try_lookup(host)? {
+            Some(found) => Some(found),
+            None => self.fallback.as_ref().map(|router| (router, Vec::new())),
+        })
+    }
+}
+
+/// The route pattern matched when routing a request via `route_request`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MatchedRoute(RoutePattern);
+
+impl MatchedRoute {
+    /// Retrieves the matched route pattern as a string slice.
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    /// Retrieves the matched route pattern.
+    pub fn pattern(&self) -> &RoutePattern {
+        &self.0
+    }
+}
+
+/// Extension trait to access routing values stored in a HTTP request.
+///
+/// These values are only available after the request has been routed via
+/// `HttpRouter::route_request` (or any of the integrations built on top of it).
+pub trait RequestExt {
+    /// Retrieves the parameters captured during routing.
+    fn params(&self) -> Option<&Params>;
+
+    /// Retrieves a parameter captured during routing by name.
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params().and_then(|params| params.get(name))
+    }
+
+    /// Retrieves the pattern of the route matched during routing.
+    fn matched_route(&self) -> Option<&str>;
+}
+
+impl<B> RequestExt for Request<B> {
+    fn params(&self) -> Option<&Params> {
+        self.extensions().get()
+    }
+
+    fn matched_route(&self) -> Option<&str> {
+        self.extensions().get().map(MatchedRoute::as_str)
+    }
+}
+
+/// Retrieves the host of a HTTP request, without any port.
+///
+/// The host is taken from the `Host` header of the request, falling back to
+/// the authority of the request URI in the case that there is no header set.
+/// This is the same host used by `HostRouter::request_handler`, and so it can
+/// be used to resolve the bounds of any host captures.
+pub fn request_host<B>(req: &Request<B>) -> Option<&str> {
+    req.headers()
+        .get(HOST)
+        .and_then(|host| host.to_str().ok())
+        .or_else(|| req.uri().authority().map(|auth| auth.as_str()))
+        .map(strip_port)
+}
+
+/// Creates an empty response with the provided status code.
+#[cfg(any(feature = "hyper", feature = "tower"))]
+pub(crate) fn status_response<B: Default>(status: StatusCode) -> Response<B> {
+    let mut response = Response::new(B::default());
+    *response.status_mut() = status;
+    response
+}
+
+/// Creates an empty response with an `Allow` header of the provided methods.
+#[cfg(any(feature = "hyper", feature = "tower"))]
+pub(crate) fn allowed_response<B: Default>(status: StatusCode, allowed: &[Method]) -> Response<B> {
+    let allowed: Vec<&str> = allowed.iter().map(Method::as_str).collect();
+    let allowed = HeaderValue::from_str(&allowed.join(", ")).expect("invalid method");
+
+    let mut response = status_response(status);
+    response.headers_mut().insert(ALLOW, allowed);
+    response
+}
+
+/// Strips a port from a host value, taking care to handle IPv6 addresses.
+fn strip_port(host: &str) -> &str {
+    if host.starts_with('[') {
+        return match host.find(']') {
+            Some(idx) => &host[..=idx],
+            None => host,
+        };
+    }
+
+    match host.rfind(':') {
+        Some(idx) => &host[..idx],
+        None => host,
+    }
+}
+
+/// A scoped builder used to register routes underneath a path prefix.
+///
+/// Scopes are created via `HttpRouter::scope` and are able to nest, with each
+/// nested scope joining its prefix onto the prefix of the parent scope. Every
+/// registration is passed through to the matching method of the `HttpRouter`.
+///
+/// Each scope can also carry a set of wrapping functions, which are applied to
+/// every value registered inside the scope (including inside nested scopes). This
+/// can be used to attach shared data such as tags or middleware to all routes in
+/// a scope. Wrappers are applied innermost first, so the wrapper of the outermost
+/// scope will always be the outermost layer of the final value.
+pub struct Scope<'a, T> {
+    router: &'a mut HttpRouter<T>,
+    prefix: String,
+    wrappers: Vec<Rc<dyn Fn(T) -> T + 'a>>,
+}
+
+impl<'a, T> Scope<'a, T> {
+    // Automatic HTTP method delegates.
+    http_delegate!(connect, Method::CONNECT, "CONNECT");
+    http_delegate!(delete, Method::DELETE, "DELETE");
+    http_delegate!(get, Method::GET, "GET");
+    http_delegate!(head, Method::HEAD, "HEAD");
+    http_delegate!(options, Method::OPTIONS, "OPTIONS");
+    http_delegate!(patch, Method::PATCH, "PATCH");
+    http_delegate!(post, Method::POST, "POST");
+    http_delegate!(put, Method::PUT, "PUT");
+    http_delegate!(trace, Method::TRACE, "TRACE");
+
+    /// Registers a group of routes underneath a nested path prefix.
+    ///
+    /// The nested scope inherits all wrappers registered on this scope at the
+    /// time of the call, and any wrappers added to the nested scope only apply
+    /// to routes registered inside the nested scope.
+    pub fn scope<F>(&mut self, prefix: &str, f: F)
+    where
+        F: FnOnce(&mut Scope<T>),
+    {
+        f(&mut Scope {
+            router: &mut *self.router,
+            prefix: join_path(&self.prefix, prefix),
+            wrappers: self.wrappers.clone(),
+        })
+    }
+
+    /// Adds a wrapping function to apply to all values registered in this scope.
+    ///
+    /// Wrappers only apply to values registered after they're added, so they
+    /// should typically be added before registering any routes in the scope.
+    pub fn wrap<F>(&mut self, f: F)
+    where
+        F: Fn(T) -> T + 'a,
+    {
+        self.wrappers.push(Rc::new(f));
+    }
+
+    /// Registers a handler for the provided HTTP method.
+    ///
+    /// See `HttpRouter::route` for further details.
+    pub fn route<P>(&mut self, method: Method, path: P, t: T)
+    where
+        P: AsRef<str>,
+    {
+        registered(self.try_route(method, path, t))
+    }
+
+    /// Attempts to register a handler for the provided HTTP method.
+    ///
+    /// See `HttpRouter::try_route` for further details.
+    pub fn try_route<P>(&mut self, method: Method, path: P, t: T) -> Result<(), LimitError>
+    where
+        P: AsRef<str>,
+    {
+        let (path, t) = self.prepare(path.as_ref(), t);
+        self.router.try_route(method, &path, t)
+    }
+
+    /// Registers a guarded handler for the provided HTTP method.
+    ///
+    /// See `HttpRouter::guarded` for further details.
+    pub fn guarded<P, G>(&mut self, method: Method, path: P, guard: G, t: T)
+    where
+        P: AsRef<str>,
+        G: Guard,
+    {
+        registered(self.try_guarded(method, path, guard, t))
+    }
+
+    /// Attempts to register a guarded handler for the provided HTTP method.
+    ///
+    /// See `HttpRouter::try_guarded` for further details.
+    pub fn try_guarded<P, G>(
+        &mut self,
+        method: Method,
+        path: P,
+        guard: G,
+        t: T,
+    ) -> Result<(), LimitError>
+    where
+        P: AsRef<str>,
+        G: Guard,
+    {
+        let (path, t) = self.prepare(path.as_ref(), t);
+        self.router.try_guarded(method, &path, guard, t)
+    }
+
+    /// Registers a handler for each of the provided HTTP methods.
+    ///
+    /// See `HttpRouter::route_many` for further details.
+    pub fn route_many<I, P>(&mut self, methods: I, path: P, t: T)
+    where
+        I: IntoIterator<Item = Method>,
+        P: AsRef<str>,
+        T: Clone,
+    {
+        registered(self.try_route_many(methods, path, t))
+    }
+
+    /// Attempts to register a handler for each of the provided HTTP methods.
+    ///
+    /// See `HttpRouter::try_route_many` for further details.
+    pub fn try_route_many<I, P>(&mut self, methods: I, path: P, t: T) -> Result<(), LimitError>
+    where
+        I: IntoIterator<Item = Method>,
+        P: AsRef<str>,
+        T: Clone,
+    {
+        let (path, t) = self.prepare(path.as_ref(), t);
+        self.router.try_route_many(methods, &path, t)
+    }
+
+    /// Registers a handler for any HTTP method.
+    ///
+    /// See `HttpRouter::any` for further details.
+    pub fn any<P>(&mut self, path: P, t: T)
+    where
+        P: AsRef<str>,
+    {
+        registered(self.try_any(path, t))
+    }
+
+    /// Attempts to register a handler for any HTTP method.
+    ///
+    /// See `HttpRouter::try_any` for further details.
+    pub fn try_any<P>(&mut self, path: P, t: T) -> Result<(), LimitError>
+    where
+        P: AsRef<str>,
+    {
+        let (path, t) = self.prepare(path.as_ref(), t);
+        self.router.try_any(&path, t)
+    }
+
+    /// Prepares a path/handler pair for registration within this scope.
+    fn prepare(&self, path: &str, t: T) -> (String, T) {
+        let t = self.wrappers.iter().rev().fold(t, |t, wrap| wrap(t));
+        let path = join_path(&self.prefix, path);
+
+        (path, t)
+    }
+}
+
+/// Unwraps the result of a registration, panicking if a limit was exceeded.
+fn registered(result: Result<(), LimitError>) {
+    if let Err(err) = result {
+        panic!("{}", err);
+    }
+}
+
+/// Merges an incoming handler into an existing slot, using a conflict policy.
+fn merge_handler<T>(existing: &mut Option<T>, incoming: Option<T>, conflict: Conflict) {
+    if let Some(incoming) = incoming {
+        if existing.is_none() || conflict != Conflict::Keep {
+            existing.replace(incoming);
+        }
+    }
+}
+
+/// Joins a path onto a prefix, ensuring there's a separator between them.
+fn join_path(prefix: &str, path: &str) -> String {
+    let prefix = prefix.trim_end_matches('/');
+    let path = path.trim_start_matches('/');
+
+    format!("{}/{}", prefix, path)
 }
@@ -0,0 +1,315 @@
+//! Hyper server integration for the `HttpRouter`.
+//!
+//! This module exposes a `Handler` trait for asynchronous request handlers,
+//! which receive a request alongside the `Params` captured during routing. A
+//! `HttpRouter` of handlers can then be turned directly into a service used
+//! to serve a Hyper server, via `HttpRouter::into_make_service`:
+//!
+//! ```rust,ignore
+//! let mut router = HttpRouter::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+//!
+//! router.get("/:name", handler(|_req, params| async move {
+//!     let body = format!("Hello, {}!\n", params.get("name").unwrap());
+//!     Ok::<_, Error>(Response::new(body.into()))
+//! }));
+//!
+//! Server::bind(&addr).serve(router.into_make_service()).await?;
+//! ```
+//!
+//! Any errors returned by handlers are passed through a single error hook,
+//! which can be configured via `MakeService::on_error`.
+//!
+//! Handlers can also be wrapped in `Middleware`, either individually via the
+//! `layer` function or for every route in a `Scope` via the `middleware`
+//! function (i.e. `scope.wrap(middleware(auth))`). Middleware is run in the
+//! order it wraps a handler; the middleware of the outermost scope runs first,
+//! and any middleware attached directly to a route runs last.
+//!
+//! To activate this extension, use the `"hyper"` Cargo feature.
+use hyper::service::Service;
+use hyper::{Body, Request, Response, StatusCode};
+
+use std::convert::Infallible;
+use std::future::{ready, Future, Ready};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use crate::capture::Params;
+use crate::http::{allowed_response, status_response, HttpRouter, Lookup};
+
+/// Error type returned by a `Handler`.
+pub type Error = Box<dyn std::error::Error + Send + Sync>;
+
+/// Boxed future type returned by a `Handler`.
+pub type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+/// Asynchronous request handler for use in a `HttpRouter`.
+///
+/// This trait is implemented for any function (or closure) which receives a
+/// request and the captured `Params`, and returns a future resolving to a
+/// response. Handlers can be stored in a router as a `Box<dyn Handler>` to
+/// allow for many different handler types within a single router.
+pub trait Handler: Send + Sync + 'static {
+    /// Handles a request, with the parameters captured during routing.
+    fn call(&self, req: Request<Body>, params: Params) -> BoxFuture<Result<Response<Body>, Error>>;
+}
+
+/// Blanket implementation of `Handler` for asynchronous functions.
+impl<F, Fut, E> Handler for F
+where
+    F: Fn(Request<Body>, Params) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<Response<Body>, E>> + Send + 'static,
+    E: Into<Error>,
+{
+    fn call(&self, req: Request<Body>, params: Params) -> BoxFuture<Result<Response<Body>, Error>> {
+        let future = self(req, params);
+        Box::pin(async move { future.await.map_err(Into::into) })
+    }
+}
+
+/// Implementation of `Handler` for boxed handlers.
+impl Handler for Box<dyn Handler> {
+    fn call(&self, req: Request<Body>, params: Params) -> BoxFuture<Result<Response<Body>, Error>> {
+        (**self).call(req, params)
+    }
+}
+
+/// Creates a boxed `Handler` from an asynchronous function.
+///
+/// This is mainly provided to help the compiler infer the argument types of
+/// closures, which it is unable to do when coercing into a `Box<dyn Handler>`.
+pub fn handler<F, Fut, E>(f: F) -> Box<dyn Handler>
+where
+    F: Fn(Request<Body>, Params) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<Response<Body>, E>> + Send + 'static,
+    E: Into<Error>,
+{
+    Box::new(f)
+}
+
+/// Middleware which can wrap the invocation of a `Handler`.
+///
+/// Each middleware receives the request and the `Params` captured during
+/// routing, alongside a `Next` used to invoke the rest of the chain. This
+/// allows middleware to modify the request before passing it through, to
+/// modify the response after it's returned, or to return a response early.
+pub trait Middleware: Send + Sync + 'static {
+    /// Handles a request, with the remaining chain of handlers.
+    fn call(
+        &self,
+        req: Request<Body>,
+        params: Params,
+        next: Next,
+    ) -> BoxFuture<Result<Response<Body>, Error>>;
+}
+
+/// Blanket implementation of `Middleware` for asynchronous functions.
+impl<F, Fut, E> Middleware for F
+where
+    F: Fn(Request<Body>, Params, Next) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<Response<Body>, E>> + Send + 'static,
+    E: Into<Error>,
+{
+    fn call(
+        &self,
+        req: Request<Body>,
+        params: Params,
+        next: Next,
+    ) -> BoxFuture<Result<Response<Body>, Error>> {
+        let future = self(req, params, next);
+        Box::pin(async move { future.await.map_err(Into::into) })
+    }
+}
+
+/// The remaining chain of handlers passed to a `Middleware`.
+pub struct Next {
+    handler: Arc<dyn Handler>,
+}
+
+impl Next {
+    /// Runs the remaining chain of handlers with the provided request.
+    pub fn run(
+        self,
+        req: Request<Body>,
+        params: Params,
+    ) -> BoxFuture<Result<Response<Body>, Error>> {
+        self.handler.call(req, params)
+    }
+}
+
+/// Handler type used to wrap a `Handler` in a `Middleware`.
+struct Layered {
+    middleware: Arc<dyn Middleware>,
+    handler: Arc<dyn Handler>,
+}
+
+impl Handler for Layered {
+    fn call(&self, req: Request<Body>, params: Params) -> BoxFuture<Result<Response<Body>, Error>> {
+        let next = Next {
+            handler: self.handler.clone(),
+        };
+        self.middleware.call(req, params, next)
+    }
+}
+
+/// Wraps a boxed `Handler` inside a `Middleware`.
+///
+/// The middleware will run before the handler is invoked, and any layers
+/// added afterward (such as those of a `Scope`) will run before this one.
+pub fn layer<M>(middleware: M, handler: Box<dyn Handler>) -> Box<dyn Handler>
+where
+    M: Middleware,
+{
+    Box::new(Layered {
+        middleware: Arc::new(middleware),
+        handler: Arc::from(handler),
+    })
+}
+
+/// Creates a wrapping function which applies a `Middleware` to handlers.
+///
+/// This is designed for use with `Scope::wrap`, to apply the middleware to
+/// every route registered within the scope (including any nested scopes). The
+/// middleware itself is shared between every route it is applied to.
+pub fn middleware<M>(middleware: M) -> impl Fn(Box<dyn Handler>) -> Box<dyn Handler>
+where
+    M: Middleware,
+{
+    let middleware: Arc<dyn Middleware> = Arc::new(middleware);
+    move |handler| {
+        Box::new(Layered {
+            middleware: middleware.clone(),
+            handler: Arc::from(handler),
+        })
+    }
+}
+
+impl<H> HttpRouter<H>
+where
+    H: Handler,
+{
+    /// Converts this router into a service used to create connection services.
+    ///
+    /// The returned `MakeService` can be provided directly to a Hyper server,
+    /// and will share this router across all connections to the server.
+    pub fn into_make_service(self) -> MakeService<H> {
+        MakeService {
+            inner: RouterService {
+                router: Arc::new(self),
+                on_error: Arc::new(error_response),
+            },
+        }
+    }
+}
+
+/// Service used to create a `RouterService` for each connection.
+pub struct MakeService<H> {
+    inner: RouterService<H>,
+}
+
+impl<H> MakeService<H> {
+    /// Sets the hook used to turn handler errors into responses.
+    ///
+    /// By default any errors are turned into an empty `500` response.
+    pub fn on_error<F>(mut self, f: F) -> Self
+    where
+        F: Fn(Error) -> Response<Body> + Send + Sync + 'static,
+    {
+        self.inner.on_error = Arc::new(f);
+        self
+    }
+}
+
+impl<H, T> Service<T> for MakeService<H> {
+    type Response = RouterService<H>;
+    type Error = Infallible;
+    type Future = Ready<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _target: T) -> Self::Future {
+        ready(Ok(self.inner.clone()))
+    }
+}
+
+/// Service used to dispatch requests to the handlers in a router.
+///
+/// Requests are routed via `HttpRouter::route_request`, with a `405` returned
+/// for paths which exist without a handler for the method (along with the
+/// `Allow` header). Requests which match no route are passed to the fallback
+/// handler of the router if there is one, or an empty `404` otherwise. Paths
+/// rejected by the normalizer or limits of the router result in an empty `400`.
+pub struct RouterService<H> {
+    router: Arc<HttpRouter<H>>,
+    on_error: Arc<dyn Fn(Error) -> Response<Body> + Send + Sync>,
+}
+
+impl<H> Clone for RouterService<H> {
+    fn clone(&self) -> Self {
+        Self {
+            router: self.router.clone(),
+            on_error: self.on_error.clone(),
+        }
+    }
+}
+
+impl<H> Service<Request<Body>> for RouterService<H>
+where
+    H: Handler,
+{
+    type Response = Response<Body>;
+    type Error = Infallible;
+    type Future = BoxFuture<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, mut req: Request<Body>) -> Self::Future {
+        let router = self.router.clone();
+        let on_error = self.on_error.clone();
+
+        Box::pin(async move {
+            // route the request, storing any routing values in the request
+            let (handler, head) = match router.route_request(&mut req) {
+                Lookup::Found(handler, _) => (handler, false),
+                Lookup::Head(handler, _) => (handler, true),
+                Lookup::Options(allowed) => {
+                    return Ok(allowed_response(StatusCode::OK, &allowed));
+                }
+                Lookup::MethodNotAllowed(allowed) => {
+                    let status = StatusCode::METHOD_NOT_ALLOWED;
+                    return Ok(allowed_response(status, &allowed));
+                }
+                Lookup::InvalidPath => return Ok(status_response(StatusCode::BAD_REQUEST)),
+                Lookup::NotFound => match router.fallback_handler() {
+                    Some(handler) => (handler, false),
+                    None => return Ok(status_response(StatusCode::NOT_FOUND)),
+                },
+            };
+
+            // handlers receive the parameters moved out of the request
+            let params = req.extensions_mut().remove().unwrap_or_default();
+
+            // invoke the handler, passing any errors through the hook
+            let mut response = match handler.call(req, params).await {
+                Ok(response) => response,
+                Err(err) => on_error(err),
+            };
+
+            if head {
+                *response.body_mut() = Body::empty();
+            }
+
+            Ok(response)
+        })
+    }
+}
+
+/// Default error hook, which turns any error into an empty `500` response.
+fn error_response(_err: Error) -> Response<Body> {
+    status_response(StatusCode::INTERNAL_SERVER_ERROR)
+}
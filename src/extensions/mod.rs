@@ -10,4 +10,16 @@
 //! include the module. In general the name of the extension will match the
 //! feature, but this isn't always possible due to some Cargo behaviour.
 #[cfg(feature = "web")]
+pub mod guard;
+#[cfg(feature = "web")]
 pub mod http;
+#[cfg(feature = "hyper")]
+pub mod hyper;
+#[cfg(feature = "openapi")]
+pub mod openapi;
+#[cfg(feature = "serde")]
+pub mod serde;
+#[cfg(feature = "shared")]
+pub mod shared;
+#[cfg(feature = "tower")]
+pub mod tower;
@@ -0,0 +1,472 @@
+//! OpenAPI document generation for the `HttpRouter`.
+//!
+//! This module walks the routes registered in a `HttpRouter` to produce the
+//! `paths` object of an OpenAPI 3 document, so that a specification can be
+//! generated from the router rather than being maintained separately:
+//!
+//! ```rust,ignore
+//! let paths = router.openapi_paths(|handler| {
+//!     Operation::new()
+//!         .summary(handler.summary())
+//!         .tag("users")
+//!         .operation_id(handler.name())
+//! });
+//! ```
+//!
+//! Routes are translated via `RoutePattern`, so parameters such as `:id` become
+//! templated segments such as `{id}`, with a path parameter generated for each
+//! of them. Each parameter is documented using the `Matcher::schema_type` of
+//! the matcher of its segment, and as a string if the matcher has no type; this
+//! can be overridden per operation via `Operation::parameter`. The constraint
+//! of a constrained parameter is documented as the `pattern` of its schema. As
+//! OpenAPI has no templating for several segments, a wildcard is documented as
+//! a single templated segment (even though its value may contain a `/`).
+//!
+//! Handlers registered via `HttpRouter::any` have no method to be documented
+//! against, and so they're not included in the generated document. When several
+//! guarded handlers exist for a method, the unguarded handler is documented (or
+//! the first guarded handler if there is no unguarded handler).
+//!
+//! Going the other way, a `HttpRouter` can also be loaded from the `paths` of an
+//! existing OpenAPI document via `from_json` (or `from_yaml`). Each route of the
+//! loaded router carries the `OperationId` of its operation, and handlers can be
+//! bound to these identifiers via `HttpRouter::bind`:
+//!
+//! ```rust,ignore
+//! let router = openapi::from_json(&spec, parsers)?.bind(|id| handlers.remove(id.as_str()))?;
+//! ```
+//!
+//! To activate this extension, use the `"openapi"` Cargo feature. Loading from
+//! YAML documents also requires the `"openapi-yaml"` Cargo feature.
+use http::Method;
+use serde_json::{json, Map, Value};
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+
+use crate::http::{HttpRouter, Lookup, Route};
+use crate::parser::Parser;
+use crate::pattern::Segment;
+
+/// Metadata attached to an operation in a generated OpenAPI document.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Operation {
+    summary: Option<String>,
+    tags: Vec<String>,
+    operation_id: Option<String>,
+    parameters: BTreeMap<String, Value>,
+}
+
+impl Operation {
+    /// Creates a new `Operation` without any metadata.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the summary of the operation.
+    pub fn summary<S: Into<String>>(mut self, summary: S) -> Self {
+        self.summary = Some(summary.into());
+        self
+    }
+
+    /// Adds a tag to the operation.
+    pub fn tag<S: Into<String>>(mut self, tag: S) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+
+    /// Sets the identifier of the operation.
+    pub fn operation_id<S: Into<String>>(mut self, operation_id: S) -> Self {
+        self.operation_id = Some(operation_id.into());
+        self
+    }
+
+    /// Sets the schema of a path parameter, replacing the schema of its matcher.
+    pub fn parameter<S: Into<String>>(mut self, name: S, schema: Value) -> Self {
+        self.parameters.insert(name.into(), schema);
+        self
+    }
+
+    /// Converts this operation into an OpenAPI operation object.
+    fn into_value(self, params: &[(String, Value)]) -> Value {
+        let mut operation = Map::new();
+
+        if let Some(summary) = self.summary {
+            operation.insert("summary".to_owned(), summary.into());
+        }
+
+        if let Some(operation_id) = self.operation_id {
+            operation.insert("operationId".to_owned(), operation_id.into());
+        }
+
+        if !self.tags.is_empty() {
+            operation.insert("tags".to_owned(), self.tags.into());
+        }
+
+        if !params.is_empty() {
+            let mut parameters = self.parameters;
+            let parameters: Vec<Value> = params
+                .iter()
+                .map(|(name, schema)| {
+                    let schema = parameters.remove(name).unwrap_or_else(|| schema.clone());
+
+                    json!({
+                        "name": name,
+                        "in": "path",
+                        "required": true,
+                        "schema": schema,
+                    })
+                })
+                .collect();
+
+            operation.insert("parameters".to_owned(), parameters.into());
+        }
+
+        operation.insert(
+            "responses".to_owned(),
+            json!({ "default": { "description": "Default response" } }),
+        );
+
+        Value::Object(operation)
+    }
+}
+
+impl<T> HttpRouter<T> {
+    /// Generates the `paths` object of an OpenAPI document for this router.
+    ///
+    /// The provided function is called for every documented handler, and is
+    /// used to attach metadata such as the summary and tags to the operation.
+    pub fn openapi_paths<F>(&self, mut f: F) -> Value
+    where
+        F: FnMut(&T) -> Operation,
+    {
+        let mut selected: BTreeMap<(String, String), Route<'_, T>> = BTreeMap::new();
+
+        for route in self.routes() {
+            let method = match route.method() {
+                Some(method) => method.as_str().to_ascii_lowercase(),
+                None => continue,
+            };
+
+            let guarded = route.guard().is_some();
+            let key = (route.path().to_string(), method);
+
+            // unguarded handlers take priority over guarded handlers
+            match selected.get(&key) {
+                Some(existing) if existing.guard().is_none() => continue,
+                Some(_) if guarded => continue,
+                _ => selected.insert(key, route),
+            };
+        }
+
+        let mut paths = Map::new();
+
+        for ((_, method), route) in selected {
+            let (template, params) = template_path(&route);
+
+            let operation = f(route.handler()).into_value(&params);
+
+            paths
+                .entry(template)
+                .or_insert_with(|| Value::Object(Map::new()))
+                .as_object_mut()
+                .expect("path item must be an object")
+                .insert(method, operation);
+        }
+
+        Value::Object(paths)
+    }
+}
+
+/// Translates the pattern of a route into an OpenAPI path template.
+///
+/// This returns the templated path alongside the name and default schema of
+/// each parameter.
+fn template_path<T>(route: &Route<'_, T>) -> (String, Vec<(String, Value)>) {
+    let mut template = String::new();
+    let mut params = Vec::new();
+
+    for (segment, matcher) in route.path().segments().iter().zip(route.matchers()) {
+        template.push('/');
+
+        let name = match segment {
+            Segment::Literal(literal) => {
+                template.push_str(literal);
+                continue;
+            }
+            Segment::Parameter(name) | Segment::Wildcard(name) => name,
+            Segment::Constrained { name, .. } => name,
+        };
+
+        let mut schema = json!({ "type": matcher.schema_type().unwrap_or("string") });
+
+        if let Segment::Constrained { constraint, .. } = segment {
+            schema["pattern"] = format!("^(?:{})$", constraint).into();
+        }
+
+        template.push_str(&format!("{{{}}}", name));
+        params.push((name.clone(), schema));
+    }
+
+    if template.is_empty() {
+        template.push('/');
+    }
+
+    (template, params)
+}
+
+/// The identifier of an operation loaded from an OpenAPI document.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct OperationId(String);
+
+impl OperationId {
+    /// Retrieves the operation identifier as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for OperationId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Error type returned when loading a router from an OpenAPI document fails.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LoadError {
+    /// The document could not be parsed.
+    Syntax(String),
+    /// The document does not contain a `paths` object.
+    MissingPaths,
+    /// A path uses a template which cannot be represented by a router.
+    ///
+    /// Only templates which span an entire segment (i.e. `/users/{id}`) are
+    /// supported, as routers do not match partial segments.
+    UnsupportedPath(String),
+    /// An operation does not have an `operationId` set.
+    MissingOperationId {
+        /// The path of the operation.
+        path: String,
+        /// The method of the operation.
+        method: Method,
+    },
+    /// An operation would be registered against the route of another operation.
+    ConflictingOperations {
+        /// The path of the second operation.
+        path: String,
+        /// The method of both operations.
+        method: Method,
+        /// The identifier of the operation registered first.
+        first: OperationId,
+        /// The identifier of the operation which conflicts with the first.
+        second: OperationId,
+    },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::Syntax(err) => write!(f, "invalid OpenAPI document: {}", err),
+            LoadError::MissingPaths => write!(f, "OpenAPI document has no paths"),
+            LoadError::UnsupportedPath(path) => write!(f, "unsupported path template: {}", path),
+            LoadError::MissingOperationId { path, method } => {
+                write!(f, "missing operationId for {} {}", method, path)
+            }
+            LoadError::ConflictingOperations {
+                path,
+                method,
+                first,
+                second,
+            } => write!(
+                f,
+                "operations {} and {} both route to {} {}",
+                first, second, method, path
+            ),
+        }
+    }
+}
+
+impl Error for LoadError {}
+
+/// Error type returned when binding handlers to a router fails.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BindError {
+    missing: Vec<OperationId>,
+}
+
+impl BindError {
+    /// Retrieves the identifiers of all operations which have no handler.
+    pub fn missing(&self) -> &[OperationId] {
+        &self.missing
+    }
+}
+
+impl fmt::Display for BindError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let missing: Vec<&str> = self.missing.iter().map(OperationId::as_str).collect();
+        write!(
+            f,
+            "no handlers bound for operations: {}",
+            missing.join(", ")
+        )
+    }
+}
+
+impl Error for BindError {}
+
+impl HttpRouter<OperationId> {
+    /// Binds a handler to every operation in this router.
+    ///
+    /// The provided function is called with each `OperationId` in the router,
+    /// and should return the handler for the operation. If there are any
+    /// operations without a handler, an error containing all of them is
+    /// returned so that the problem can be reported during startup.
+    pub fn bind<H, F>(self, mut f: F) -> Result<HttpRouter<H>, BindError>
+    where
+        F: FnMut(&OperationId) -> Option<H>,
+    {
+        let mut missing = Vec::new();
+        let router = self.map(|id| {
+            let handler = f(&id);
+            if handler.is_none() && !missing.contains(&id) {
+                missing.push(id);
+            }
+            handler
+        });
+
+        if !missing.is_empty() {
+            return Err(BindError { missing });
+        }
+
+        Ok(router.map(|handler| handler.expect("handler must be bound")))
+    }
+}
+
+/// Loads a router from an OpenAPI document in JSON format.
+///
+/// See `from_value` for details on how the document is loaded.
+pub fn from_json(
+    document: &str,
+    parsers: Vec<Box<dyn Parser>>,
+) -> Result<HttpRouter<OperationId>, LoadError> {
+    let document =
+        serde_json::from_str(document).map_err(|err| LoadError::Syntax(err.to_string()))?;
+    from_value(&document, parsers)
+}
+
+/// Loads a router from an OpenAPI document in YAML format.
+///
+/// See `from_value` for details on how the document is loaded.
+#[cfg(feature = "openapi-yaml")]
+pub fn from_yaml(
+    document: &str,
+    parsers: Vec<Box<dyn Parser>>,
+) -> Result<HttpRouter<OperationId>, LoadError> {
+    let document =
+        serde_yaml::from_str(document).map_err(|err| LoadError::Syntax(err.to_string()))?;
+    from_value(&document, parsers)
+}
+
+/// Loads a router from a parsed OpenAPI document.
+///
+/// Every operation in the `paths` object of the document is registered in the
+/// router against its method, with the value being the `operationId` of the
+/// operation. Templated segments such as `{id}` are converted into segments
+/// such as `:id`, and so the provided parsers should typically include the
+/// `DynamicParser` (or another parser which understands this syntax).
+///
+/// If two operations would be registered against the same route and method
+/// (such as `/users/{id}` and `/users/{name}`, which are the same route to the
+/// `DynamicParser`), loading fails rather than discarding either operation.
+pub fn from_value(
+    document: &Value,
+    parsers: Vec<Box<dyn Parser>>,
+) -> Result<HttpRouter<OperationId>, LoadError> {
+    let paths = document
+        .get("paths")
+        .and_then(Value::as_object)
+        .ok_or(LoadError::MissingPaths)?;
+
+    let mut router: HttpRouter<OperationId> = HttpRouter::new(parsers);
+
+    for (path, item) in paths {
+        let pattern = route_path(path).ok_or_else(|| LoadError::UnsupportedPath(path.clone()))?;
+        let item = match item.as_object() {
+            Some(item) => item,
+            None => continue,
+        };
+
+        for (method, operation) in item {
+            let method = match operation_method(method) {
+                Some(method) => method,
+                None => continue,
+            };
+
+            let id = operation
+                .get("operationId")
+                .and_then(Value::as_str)
+                .ok_or_else(|| LoadError::MissingOperationId {
+                    path: path.clone(),
+                    method: method.clone(),
+                })?;
+
+            // patterns which route to an existing operation would replace it
+            if let Lookup::Found(existing, _) = router.lookup(&method, &pattern) {
+                return Err(LoadError::ConflictingOperations {
+                    path: path.clone(),
+                    method,
+                    first: existing.clone(),
+                    second: OperationId(id.to_owned()),
+                });
+            }
+
+            router.route(method, &pattern, OperationId(id.to_owned()));
+        }
+    }
+
+    Ok(router)
+}
+
+/// Translates an OpenAPI path template into a route pattern.
+///
+/// Literal segments are escaped where needed, so that a literal such as `:name`
+/// is never parsed as a parameter (or a wildcard) by the router.
+fn route_path(path: &str) -> Option<String> {
+    let segments: Option<Vec<String>> = path
+        .split('/')
+        .map(|segment| {
+            if !segment.contains(['{', '}']) {
+                return Some(Segment::Literal(segment.to_owned()).to_string());
+            }
+
+            let name = segment.strip_prefix('{')?.strip_suffix('}')?;
+
+            if name.is_empty() || name.contains(['{', '}']) {
+                return None;
+            }
+
+            Some(format!(":{}", name))
+        })
+        .collect();
+
+    segments.map(|segments| segments.join("/"))
+}
+
+/// Retrieves the method of an operation within an OpenAPI path item.
+///
+/// Any other fields of the path item (such as shared parameters) are ignored.
+fn operation_method(field: &str) -> Option<Method> {
+    match field {
+        "get" => Some(Method::GET),
+        "put" => Some(Method::PUT),
+        "post" => Some(Method::POST),
+        "delete" => Some(Method::DELETE),
+        "options" => Some(Method::OPTIONS),
+        "head" => Some(Method::HEAD),
+        "patch" => Some(Method::PATCH),
+        "trace" => Some(Method::TRACE),
+        _ => None,
+    }
+}
@@ -0,0 +1,414 @@
+//! Deserialization of captured parameters via Serde.
+//!
+//! This module implements a Serde `Deserializer` over a set of `Params`, so
+//! that parameters can be deserialized into typed structures rather than being
+//! parsed manually by each handler:
+//!
+//! ```rust,ignore
+//! #[derive(Deserialize)]
+//! struct UserPath {
+//!     org: String,
+//!     id: u64,
+//! }
+//!
+//! let path: UserPath = params.deserialize()?;
+//! ```
+//!
+//! Structures and maps are deserialized by parameter name, with `Option` fields
+//! being set to `None` when a parameter was not captured. Sequences can be used
+//! to collect every value captured against a name; if only a single value was
+//! captured, it will be split on `/` (as is the case with catch-all segments).
+//! Sequences and tuples can also be deserialized from the top level, in which
+//! case they will contain every parameter value in the order it was captured.
+//!
+//! To activate this extension, use the `"serde"` Cargo feature.
+use serde::de::value::StrDeserializer;
+use serde::de::{self, DeserializeSeed, Deserializer, IntoDeserializer, Visitor};
+use serde::{forward_to_deserialize_any, Deserialize};
+
+use std::error::Error as StdError;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::capture::Params;
+
+impl Params {
+    /// Deserializes this set of parameters into a typed value.
+    ///
+    /// See the module documentation for details on the supported shapes.
+    pub fn deserialize<'de, T>(&'de self) -> Result<T, Error>
+    where
+        T: Deserialize<'de>,
+    {
+        T::deserialize(ParamsDeserializer { params: self })
+    }
+}
+
+/// Error type returned when deserializing parameters fails.
+///
+/// Wherever possible, the error will contain the name of the parameter which
+/// caused the error, to make it easier to report back to a caller.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error {
+    field: Option<String>,
+    message: String,
+}
+
+impl Error {
+    /// Retrieves the name of the parameter which caused the error, if any.
+    pub fn field(&self) -> Option<&str> {
+        self.field.as_deref()
+    }
+
+    /// Retrieves the message describing the error.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Attaches a field name to this error, if one is not already attached.
+    fn with_field(mut self, field: &str) -> Self {
+        if self.field.is_none() {
+            self.field = Some(field.to_owned());
+        }
+        self
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.field {
+            Some(field) => write!(f, "invalid parameter `{}`: {}", field, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl StdError for Error {}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self {
+            field: None,
+            message: msg.to_string(),
+        }
+    }
+}
+
+/// Deserializer over an entire set of parameters.
+struct ParamsDeserializer<'de> {
+    params: &'de Params,
+}
+
+impl<'de> ParamsDeserializer<'de> {
+    /// Groups the parameter values by name, retaining the captured order.
+    fn grouped(&self) -> Vec<(&'de str, Vec<&'de str>)> {
+        let mut grouped: Vec<(&str, Vec<&str>)> = Vec::new();
+        for (name, value) in self.params.iter() {
+            match grouped.iter_mut().find(|(n, _)| *n == name) {
+                Some((_, values)) => values.push(value),
+                None => grouped.push((name, vec![value])),
+            }
+        }
+        grouped
+    }
+
+    /// Retrieves a deserializer for the only value in the set of parameters.
+    fn single(&self) -> Result<ValueDeserializer<'de>, Error> {
+        match self.grouped().pop() {
+            Some((name, values)) if self.params.len() == 1 => Ok(ValueDeserializer {
+                field: Some(name),
+                values,
+            }),
+            _ => Err(de::Error::custom(format!(
+                "expected a single parameter, found {}",
+                self.params.len()
+            ))),
+        }
+    }
+}
+
+/// Delegates a deserialization method to the single parameter value.
+macro_rules! single_delegate {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                let single = self.single()?;
+                let field = single.field.unwrap_or_default();
+                single.$method(visitor).map_err(|err| err.with_field(field))
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for ParamsDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_map(MapAccess {
+            entries: self.grouped().into_iter(),
+            value: None,
+        })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let values: Vec<(&str, &str)> = self.params.iter().collect();
+        visitor.visit_seq(SeqAccess {
+            values: values.into_iter(),
+        })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if self.params.is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.single()?.deserialize_enum(name, variants, visitor)
+    }
+
+    single_delegate! {
+        deserialize_bool deserialize_char deserialize_str deserialize_string
+        deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64 deserialize_i128
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64 deserialize_u128
+        deserialize_f32 deserialize_f64 deserialize_bytes deserialize_byte_buf
+        deserialize_identifier deserialize_ignored_any
+    }
+}
+
+/// Map access over grouped parameter values.
+struct MapAccess<'de, I>
+where
+    I: Iterator<Item = (&'de str, Vec<&'de str>)>,
+{
+    entries: I,
+    value: Option<(&'de str, Vec<&'de str>)>,
+}
+
+impl<'de, I> de::MapAccess<'de> for MapAccess<'de, I>
+where
+    I: Iterator<Item = (&'de str, Vec<&'de str>)>,
+{
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        match self.entries.next() {
+            None => Ok(None),
+            Some((name, values)) => {
+                self.value = Some((name, values));
+                let key: StrDeserializer<Error> = name.into_deserializer();
+                seed.deserialize(key).map(Some)
+            }
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let (name, values) = self.value.take().expect("value requested before key");
+        let value = ValueDeserializer {
+            field: Some(name),
+            values,
+        };
+        seed.deserialize(value).map_err(|err| err.with_field(name))
+    }
+}
+
+/// Sequence access over parameter values, in captured order.
+struct SeqAccess<I> {
+    values: I,
+}
+
+impl<'de, I> de::SeqAccess<'de> for SeqAccess<I>
+where
+    I: Iterator<Item = (&'de str, &'de str)>,
+{
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        match self.values.next() {
+            None => Ok(None),
+            Some((name, value)) => {
+                let value = ValueDeserializer {
+                    field: Some(name),
+                    values: vec![value],
+                };
+                seed.deserialize(value)
+                    .map(Some)
+                    .map_err(|err| err.with_field(name))
+            }
+        }
+    }
+}
+
+/// Deserializer over the values captured against a single parameter name.
+struct ValueDeserializer<'de> {
+    field: Option<&'de str>,
+    values: Vec<&'de str>,
+}
+
+impl<'de> ValueDeserializer<'de> {
+    /// Retrieves the first value captured for this parameter.
+    fn first(&self) -> &'de str {
+        self.values.first().copied().unwrap_or_default()
+    }
+
+    /// Parses the first value captured for this parameter.
+    fn parse<T>(&self) -> Result<T, Error>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        let value = self.first();
+        value
+            .parse()
+            .map_err(|err| de::Error::custom(format!("unable to parse {:?}: {}", value, err)))
+    }
+}
+
+/// Parses a value and passes it through to the provided visitor method.
+macro_rules! parse_delegate {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                visitor.$visit(self.parse()?)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for ValueDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_borrowed_str(self.first())
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let field = self.field.unwrap_or_default();
+        let values: Vec<(&str, &str)> = match self.values.as_slice() {
+            [value] => value
+                .split('/')
+                .filter(|s| !s.is_empty())
+                .map(|value| (field, value))
+                .collect(),
+            values => values.iter().map(|value| (field, *value)).collect(),
+        };
+        visitor.visit_seq(SeqAccess {
+            values: values.into_iter(),
+        })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let variant: StrDeserializer<Error> = self.first().into_deserializer();
+        visitor.visit_enum(variant)
+    }
+
+    parse_delegate! {
+        deserialize_bool => visit_bool,
+        deserialize_char => visit_char,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+    }
+
+    forward_to_deserialize_any! {
+        str string bytes byte_buf unit unit_struct tuple_struct map struct
+        identifier ignored_any
+    }
+}
@@ -0,0 +1,103 @@
+//! Shared routing handles which can be swapped out atomically.
+//!
+//! This module exposes the `SharedRouter` structure, which wraps a router in
+//! a handle which can be cheaply cloned across threads. Readers are able to
+//! take a snapshot of the current router without any locking, and writers
+//! can publish a fully built replacement router atomically.
+//!
+//! To activate this extension, use the `"shared"` Cargo feature.
+use arc_swap::{ArcSwap, Guard};
+
+use std::sync::Arc;
+
+/// A temporary snapshot of a router, as returned by `SharedRouter::load`.
+///
+/// This dereferences to the router, and is intended to be held only for the
+/// duration of a lookup. Use `SharedRouter::load_full` for longer lived access.
+pub type Snapshot<R> = Guard<Arc<R>>;
+
+/// A shared handle to a router which can be hot-swapped at runtime.
+///
+/// The handle is generic over the router type, so it can be used with either
+/// a `Router` or any of the extension routers (such as `HttpRouter`). Cloning a
+/// handle is cheap, and all clones will see the same underlying router.
+///
+/// Each call to `load` (or `load_full`) returns a snapshot of the router at the
+/// time of the call. Any lookups made against a snapshot will continue to work
+/// against that router even if a replacement router is published before the
+/// lookup completes; the old router is only dropped after the last snapshot
+/// referencing it is dropped.
+pub struct SharedRouter<R> {
+    inner: Arc<ArcSwap<R>>,
+}
+
+impl<R> SharedRouter<R> {
+    /// Creates a new `SharedRouter` from an initial router.
+    pub fn new(router: R) -> Self {
+        Self {
+            inner: Arc::new(ArcSwap::from_pointee(router)),
+        }
+    }
+
+    /// Retrieves a temporary snapshot of the current router.
+    ///
+    /// This never blocks on writers, and the returned snapshot will not be
+    /// affected by any routers published after the snapshot was taken. This
+    /// is the cheapest way to access the router (it typically avoids touching
+    /// the reference count entirely), so it should be used on the hot path;
+    /// holding many snapshots for a long time can slow down writers, so any
+    /// long lived access should be done via `load_full` instead.
+    #[inline]
+    pub fn load(&self) -> Snapshot<R> {
+        self.inner.load()
+    }
+
+    /// Retrieves a full reference to the current router.
+    ///
+    /// This is the same as `load`, except that the router is returned as an
+    /// `Arc`, which can be held for any amount of time without affecting any
+    /// writers (at the cost of updating the reference count of the router).
+    #[inline]
+    pub fn load_full(&self) -> Arc<R> {
+        self.inner.load_full()
+    }
+
+    /// Publishes a replacement router, discarding the current router.
+    #[inline]
+    pub fn store(&self, router: R) {
+        self.inner.store(Arc::new(router))
+    }
+
+    /// Publishes a replacement router, returning the previous router.
+    #[inline]
+    pub fn swap(&self, router: R) -> Arc<R> {
+        self.inner.swap(Arc::new(router))
+    }
+
+    /// Publishes a replacement router derived from the current router.
+    ///
+    /// The provided function may be called several times in the case that
+    /// another writer publishes a router concurrently, so that no concurrent
+    /// updates are ever lost. This is typically used alongside `Clone` to
+    /// apply a change to a copy of the current router.
+    pub fn update<F>(&self, mut f: F)
+    where
+        F: FnMut(&R) -> R,
+    {
+        self.inner.rcu(|current| f(current));
+    }
+}
+
+impl<R> Clone for SharedRouter<R> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<R> From<R> for SharedRouter<R> {
+    fn from(router: R) -> Self {
+        Self::new(router)
+    }
+}
@@ -0,0 +1,144 @@
+//! Tower `Service` integration for the `HttpRouter`.
+//!
+//! This module implements `tower::Service` for any `HttpRouter` whose handlers
+//! are themselves services, allowing a router to be used directly within any
+//! tower based stack (such as a Hyper server). Requests are dispatched to the
+//! matched service, with any captured parameters resolved into an owned set of
+//! `Params` and inserted into the extensions of the request (which can then be
+//! accessed via the `RequestExt` trait).
+//!
+//! To activate this extension, use the `"tower"` Cargo feature.
+use http::{Request, Response, StatusCode};
+use tower::util::{Oneshot, ServiceExt};
+use tower::Service;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use crate::http::{allowed_response, status_response, HttpRouter, Lookup};
+
+/// Implementation of `Service` for routers of services.
+///
+/// Each request is routed via `HttpRouter::lookup`, and then handled as follows:
+///
+/// - If a service is found, it's called with the request (after inserting the
+///   captured `Params` and `MatchedRoute` into the request extensions).
+/// - If a `GET` service is used for a `HEAD` request (via `auto_head`), the
+///   body of the response is dropped before being returned.
+/// - If the path exists but the method is not allowed, an empty `405` response
+///   is returned with the `Allow` header set (or a `200` in the case of an
+///   automatic `OPTIONS` response).
+/// - If the path is rejected by the normalizer or limits of the router (set via
+///   `HttpRouter::normalize` and `HttpRouter::limits`), an empty `400` response
+///   is returned.
+/// - If no route matches, the request is passed to the fallback service of the
+///   router (set via `HttpRouter::fallback`) or an empty `404` is returned.
+///
+/// As the service being called is only known once a request arrives, readiness
+/// of the router itself is always immediate. The matched service is cloned and
+/// driven to readiness before being called, so that any backpressure applied by
+/// the service is still respected.
+impl<S, B, R> Service<Request<B>> for HttpRouter<S>
+where
+    S: Service<Request<B>, Response = Response<R>> + Clone,
+    R: Default,
+{
+    type Response = Response<R>;
+    type Error = S::Error;
+    type Future = ResponseFuture<S, B>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, mut req: Request<B>) -> Self::Future {
+        // route the request, storing any routing values in the request
+        let (service, head) = match self.route_request(&mut req) {
+            Lookup::Found(service, _) => (service.clone(), false),
+            Lookup::Head(service, _) => (service.clone(), true),
+            Lookup::Options(allowed) => {
+                return ResponseFuture::ready(allowed_response(StatusCode::OK, &allowed));
+            }
+            Lookup::MethodNotAllowed(allowed) => {
+                let status = StatusCode::METHOD_NOT_ALLOWED;
+                return ResponseFuture::ready(allowed_response(status, &allowed));
+            }
+            Lookup::InvalidPath => {
+                return ResponseFuture::ready(status_response(StatusCode::BAD_REQUEST));
+            }
+            Lookup::NotFound => match self.fallback_handler() {
+                Some(service) => (service.clone(), false),
+                None => {
+                    let status = StatusCode::NOT_FOUND;
+                    return ResponseFuture::ready(status_response(status));
+                }
+            },
+        };
+
+        ResponseFuture {
+            inner: Inner::Service {
+                head,
+                future: Box::pin(service.oneshot(req)),
+            },
+        }
+    }
+}
+
+/// Response future for a `HttpRouter` used as a `Service`.
+pub struct ResponseFuture<S, B>
+where
+    S: Service<Request<B>>,
+{
+    inner: Inner<S, B>,
+}
+
+/// Internal states of a `ResponseFuture`.
+enum Inner<S, B>
+where
+    S: Service<Request<B>>,
+{
+    Service {
+        head: bool,
+        future: Pin<Box<Oneshot<S, Request<B>>>>,
+    },
+    Ready(Option<S::Response>),
+}
+
+impl<S, B> ResponseFuture<S, B>
+where
+    S: Service<Request<B>>,
+{
+    /// Creates a new `ResponseFuture` which resolves immediately.
+    fn ready(response: S::Response) -> Self {
+        Self {
+            inner: Inner::Ready(Some(response)),
+        }
+    }
+}
+
+impl<S, B, R> Future for ResponseFuture<S, B>
+where
+    S: Service<Request<B>, Response = Response<R>>,
+    R: Default,
+{
+    type Output = Result<Response<R>, S::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match &mut self.get_mut().inner {
+            Inner::Service { head, future } => future.as_mut().poll(cx).map_ok(|mut response| {
+                if *head {
+                    *response.body_mut() = R::default();
+                }
+                response
+            }),
+            Inner::Ready(response) => {
+                let response = response.take().expect("polled after completion");
+                Poll::Ready(Ok(response))
+            }
+        }
+    }
+}
+
+// The inner future is boxed and the ready response is never pinned.
+impl<S, B> Unpin for ResponseFuture<S, B> where S: Service<Request<B>> {}
@@ -5,12 +5,109 @@
 pub mod capture;
 pub mod matcher;
 pub mod node;
+pub mod normalize;
 pub mod parser;
+pub mod pattern;
 pub mod router;
 
 // lift extensions
 mod extensions;
-pub use extensions::*;
+#[cfg(feature = "hyper")]
+pub use extensions::hyper;
+#[cfg(feature = "openapi")]
+pub use extensions::openapi;
+#[cfg(feature = "serde")]
+pub use extensions::serde;
+#[cfg(feature = "shared")]
+pub use extensions::shared;
+#[cfg(feature = "tower")]
+pub use extensions::tower;
+#[cfg(feature = "web")]
+pub use extensions::{guard, http};
+
+/// Constructs a router from a set of routes, validated at compile time.
+///
+/// Routes are provided as a pattern and a value, optionally prefixed with the
+/// HTTP method of the route. When methods are provided, the macro expands into
+/// the construction of a `HttpRouter` (which requires the `"web"` feature), and
+/// otherwise expands into the construction of a plain `Router`. The method `ANY`
+/// is used to register a handler for any method via `HttpRouter::any`.
+///
+/// ```rust
+/// let router = usher::router! {
+///     "/" => "index",
+///     "/users/:id" => "user",
+/// };
+///
+/// assert!(router.lookup("/users/123").is_some());
+/// ```
+///
+/// The generated router uses the `DynamicParser` and `StaticParser`, and each
+/// pattern is validated during compilation using the same grammar as that of
+/// `RoutePattern`. This catches parameters without a name (or used twice) and
+/// invalid constraints, as well as routes which would overwrite another route,
+/// and segments which would be shadowed by a parameter or wildcard registered
+/// earlier at the same position (including parameters of another name):
+///
+/// ```rust,compile_fail
+/// let router = usher::router! {
+///     "/users/:id" => "user",
+///     "/users/:name/posts" => "posts",
+/// };
+/// ```
+///
+/// ```rust,compile_fail
+/// let router = usher::router! {
+///     "/files/*path" => "files",
+///     "/files/readme" => "readme",
+/// };
+/// ```
+///
+/// This macro is available via the `"macros"` Cargo feature.
+#[cfg(feature = "macros")]
+pub use usher_macros::router;
+
+/// Derives parsing and formatting of paths for an enum of routes.
+///
+/// Each variant of the enum is annotated with a `#[route("...")]` attribute
+/// containing the route pattern of the variant. Every parameter in a pattern
+/// must have a matching named field in the variant (and vice versa), and each
+/// field is parsed from the captured value via `FromStr`:
+///
+/// ```rust
+/// #[derive(Debug, PartialEq, usher::Route)]
+/// enum Route {
+///     #[route("/")]
+///     Home,
+///     #[route("/users/:id")]
+///     User { id: u64 },
+/// }
+///
+/// assert_eq!(Route::from_path("/users/123"), Some(Route::User { id: 123 }));
+/// assert_eq!(Route::User { id: 123 }.to_string(), "/users/123");
+/// ```
+///
+/// The derive generates a `router` function which creates a `Router` of parsing
+/// functions for each variant, and a `from_path` function which uses a shared
+/// instance of this router to parse paths. A `Display` implementation is also
+/// generated to format each variant back into a path. Field values are written
+/// via `normalize::encode_segment` (and decoded when parsed), so values which
+/// contain characters such as `/` will survive a round trip; empty values have
+/// no representation in a path, and so cannot be parsed back.
+///
+/// Patterns are validated in the same way as those of the `router!` macro.
+///
+/// This macro is available via the `"macros"` Cargo feature.
+#[cfg(feature = "macros")]
+pub use usher_macros::Route;
+
+// macro internals
+#[cfg(feature = "macros")]
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "web")]
+    pub use http::Method;
+}
 
 // prelude module
 pub mod prelude {
@@ -26,5 +123,6 @@ pub mod prelude {
     //! The prelude may grow over time, but it is unlikely to shrink.
     pub use super::matcher::Matcher;
     pub use super::parser::{DynamicParser, Parser, StaticParser};
+    pub use super::pattern::RoutePattern;
     pub use super::router::Router;
 }
@@ -4,6 +4,8 @@
 //! against incoming segments in order to walk through the tree correctly.
 use crate::capture::Capture;
 
+use usher_pattern::Constraint;
+
 /// Matching trait to enable generic route matching algorithms.
 ///
 /// This trait backs the main tree, enabling custom segment matching based
@@ -18,6 +20,25 @@ pub trait Matcher: Send + Sync {
 
     /// Determines whether an incoming segment is a match for a base segment.
     fn is_match(&self, segment: &str) -> bool;
+
+    /// Retrieves the schema type of any values captured by this matcher.
+    ///
+    /// This is only used to describe routes (such as in OpenAPI documents) and
+    /// should be a JSON Schema type name, such as `"integer"`. Matchers which
+    /// don't provide a type have their captures described as strings.
+    fn schema_type(&self) -> Option<&str> {
+        None
+    }
+
+    /// Determines whether this matcher spans all remaining segments of a path.
+    ///
+    /// When routing, a matcher which spans the remaining segments is provided
+    /// the rest of the path (from the current segment onward) in place of the
+    /// current segment, and routing ends at the matched node. Nodes beneath a
+    /// node with such a matcher are never reached.
+    fn is_wildcard(&self) -> bool {
+        false
+    }
 }
 
 /// Blanket implementation of `Matcher` for pure functions.
@@ -38,6 +59,7 @@ where
 ///
 /// This struct is constructed via the `StaticParser` and compares incoming
 /// segments directly against the internal static `String` segment.
+#[derive(Clone)]
 pub struct StaticMatcher {
     inner: String,
 }
@@ -60,6 +82,7 @@ impl Matcher for StaticMatcher {
 ///
 /// This struct is constructed via the `DynamicParser` and assumes that any
 /// incoming path segment is a candidate for matching.
+#[derive(Clone)]
 pub struct DynamicMatcher {
     inner: String,
 }
@@ -82,3 +105,71 @@ impl Matcher for DynamicMatcher {
         true
     }
 }
+
+/// Constrained path segment matcher.
+///
+/// This struct is constructed via the `DynamicParser` for segments such as
+/// `:id(\d+)`, and only matches segments which satisfy the constraint (which
+/// is a regular expression that must match the entire segment).
+#[derive(Clone)]
+pub struct ConstrainedMatcher {
+    inner: String,
+    constraint: Constraint,
+}
+
+impl ConstrainedMatcher {
+    /// Constructs a new `ConstrainedMatcher` from a name and constraint.
+    ///
+    /// If the constraint is not a valid regular expression, `None` is returned.
+    pub fn new<S: Into<String>>(s: S, constraint: &str) -> Option<Self> {
+        Some(Self {
+            inner: s.into(),
+            constraint: Constraint::new(constraint)?,
+        })
+    }
+}
+
+impl Matcher for ConstrainedMatcher {
+    /// Determines if there is a capture for the incoming segment.
+    fn capture<'a>(&'a self, segment: &str) -> Option<Capture<'a>> {
+        Some((&self.inner, (0, segment.len())))
+    }
+
+    /// Determines if the incoming segment satisfies the constraint.
+    fn is_match(&self, segment: &str) -> bool {
+        self.constraint.is_match(segment)
+    }
+}
+
+/// Wildcard path matcher.
+///
+/// This struct is constructed via the `DynamicParser` for segments such as
+/// `*path`, and captures all remaining segments of a path as a single value.
+#[derive(Clone)]
+pub struct WildcardMatcher {
+    inner: String,
+}
+
+impl WildcardMatcher {
+    /// Constructs a new `WildcardMatcher` from a segment.
+    pub fn new<S: Into<String>>(s: S) -> Self {
+        Self { inner: s.into() }
+    }
+}
+
+impl Matcher for WildcardMatcher {
+    /// Captures the remainder of the path.
+    fn capture<'a>(&'a self, rest: &str) -> Option<Capture<'a>> {
+        Some((&self.inner, (0, rest.len())))
+    }
+
+    /// Determines if this matcher matches the remainder of the path.
+    fn is_match(&self, _rest: &str) -> bool {
+        true
+    }
+
+    /// Spans all remaining segments of the path.
+    fn is_wildcard(&self) -> bool {
+        true
+    }
+}
@@ -1,5 +1,9 @@
 //! Nodes to represent the internal structure of a router.
 use super::matcher::Matcher;
+use super::pattern::Segment;
+
+use std::convert::Infallible;
+use std::sync::{Arc, OnceLock};
 
 /// Node structure to represent the internal structure of a router.
 ///
@@ -14,6 +18,15 @@ use super::matcher::Matcher;
 /// automatically provided to the `Node` at creation time and is
 /// calculated by the routing system.
 ///
+/// Matchers are stored behind an `Arc`, so cloning a `Node` shares each
+/// matcher with the clone rather than requiring matchers to be cloneable.
+///
+/// Each `Node` also keeps the literal segment it was parsed from, so
+/// that the tree can be walked and merged after construction without
+/// needing access to the original inputs. The name of any capture made
+/// by the matcher is interned on the first capture, to be shared by all
+/// `OwnedCaptures` created from lookups which pass through the node.
+///
 /// Lastly, a `Node` can have child instances to represent the
 /// recursive structure of a router. These children are stored in
 /// a `Vec` as there's currently no logical way to index them into
@@ -21,18 +34,23 @@ use super::matcher::Matcher;
 /// containing vector does not require any memory allocation. Any
 /// memory will be allocated lazily, and should remain minimal in
 /// most standard cases (as it depends on the allocator in use).
+#[derive(Clone)]
 pub struct Node<T> {
     value: Option<T>,
-    matcher: Box<dyn Matcher>,
+    segment: String,
+    matcher: Arc<dyn Matcher>,
+    name: OnceLock<Arc<str>>,
     children: Vec<Node<T>>,
 }
 
 impl<T> Node<T> {
     /// Constructs a new `Node` from a literal.
-    pub(crate) fn new(matcher: Box<dyn Matcher>) -> Self {
+    pub(crate) fn new<S: Into<String>>(segment: S, matcher: Box<dyn Matcher>) -> Self {
         Self {
-            matcher,
+            matcher: matcher.into(),
             value: None,
+            segment: segment.into(),
+            name: OnceLock::new(),
             children: Vec::new(),
         }
     }
@@ -53,6 +71,58 @@ impl<T> Node<T> {
         &mut self.children
     }
 
+    /// Consumes this node, returning the inner value and children.
+    pub(crate) fn into_parts(self) -> (Option<T>, Vec<Node<T>>) {
+        (self.value, self.children)
+    }
+
+    /// Retrieves the literal segment this node was parsed from.
+    pub(crate) fn segment(&self) -> &str {
+        &self.segment
+    }
+
+    /// Determines whether this node should be used for a segment being inserted.
+    ///
+    /// This is the case when the segment is identical to the segment this node
+    /// was parsed from, or when the matcher matches the segment. A literal is
+    /// matched in unescaped form, and a parameter only merges into a node which
+    /// was parsed from another parameter. Constrained parameters and wildcards
+    /// only merge into a node parsed from the same segment, as does any segment
+    /// being inserted beneath a wildcard (which would never be reached).
+    pub(crate) fn accepts(&self, segment: &str) -> bool {
+        if self.segment == segment {
+            return true;
+        }
+
+        if self.matcher.is_wildcard() {
+            return false;
+        }
+
+        match Segment::parse(segment) {
+            Ok(Segment::Literal(literal)) => self.matcher.is_match(&literal),
+            Ok(Segment::Parameter(_)) => {
+                matches!(Segment::parse(&self.segment), Ok(Segment::Parameter(_)))
+                    && self.matcher.is_match(segment)
+            }
+            Ok(_) => false,
+            Err(_) => self.matcher.is_match(segment),
+        }
+    }
+
+    /// Retrieves the interned form of a capture name made by the matcher.
+    ///
+    /// The name is interned on first use, so this only allocates if the node
+    /// has not seen the name before (or if the matcher captures several names).
+    pub(crate) fn name(&self, capture: &str) -> Arc<str> {
+        let name = self.name.get_or_init(|| Arc::from(capture));
+
+        if &**name == capture {
+            name.clone()
+        } else {
+            Arc::from(capture)
+        }
+    }
+
     /// Retrieves the matching struct for this node.
     pub(crate) fn matcher(&self) -> &dyn Matcher {
         &*self.matcher
@@ -71,4 +141,55 @@ impl<T> Node<T> {
     pub(crate) fn value(&self) -> Option<&T> {
         self.value.as_ref()
     }
+
+    /// Converts this node into a node of another type via a mapping function.
+    pub(crate) fn map<U, F>(self, f: &mut F) -> Node<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        match self.try_map(&mut |t| Ok::<_, Infallible>(f(t))) {
+            Ok(node) => node,
+            Err(err) => match err {},
+        }
+    }
+
+    /// Converts a reference to this node into a node of another type.
+    pub(crate) fn map_ref<U, F>(&self, f: &mut F) -> Node<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Node {
+            value: self.value.as_ref().map(&mut *f),
+            segment: self.segment.clone(),
+            matcher: self.matcher.clone(),
+            name: self.name.clone(),
+            children: self.children.iter().map(|c| c.map_ref(f)).collect(),
+        }
+    }
+
+    /// Attempts to convert this node into a node of another type.
+    ///
+    /// The first error returned by the mapping function will short circuit
+    /// the conversion and be passed back to the caller.
+    pub(crate) fn try_map<U, E, F>(self, f: &mut F) -> Result<Node<U>, E>
+    where
+        F: FnMut(T) -> Result<U, E>,
+    {
+        Ok(Node {
+            value: self.value.map(&mut *f).transpose()?,
+            segment: self.segment,
+            matcher: self.matcher,
+            name: self.name,
+            children: self
+                .children
+                .into_iter()
+                .map(|c| c.try_map(f))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Retrieves a mutable reference to the value of this node.
+    pub(crate) fn value_mut(&mut self) -> &mut Option<T> {
+        &mut self.value
+    }
 }
@@ -0,0 +1,254 @@
+//! Normalization of paths prior to routing.
+//!
+//! A `Router` treats every segment of a path as an opaque value, so segments
+//! such as `.` and `..` are routed just like any other segment. This means a
+//! path such as `/static/../admin` would match `/static/:file`, with `..` being
+//! captured as the file. This is rarely desirable when captures are used to
+//! access resources such as files, so this module provides a `Normalizer`
+//! which can be applied to paths before they're routed:
+//!
+//! ```rust
+//! use usher::normalize::Normalizer;
+//!
+//! let normalizer = Normalizer::new();
+//! let path = normalizer.normalize("/static/./css/../../admin").unwrap();
+//!
+//! assert_eq!(path, "/admin");
+//! ```
+//!
+//! Once normalized, a path contains no `.` or `..` segments (and no empty
+//! segments), so any captures found when routing the path are guaranteed to
+//! be free of traversal components. As encoded dots and slashes could be used
+//! to smuggle these components past normalization (to be decoded later by a
+//! handler), they're rejected by default; see `EncodedPolicy` for alternatives.
+//!
+//! Normalization can be enabled on a `HttpRouter` via `HttpRouter::normalize`,
+//! in which case it's applied automatically by `HttpRouter::route_request`.
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+
+/// Policy used when a path contains encoded dots or slashes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EncodedPolicy {
+    /// Reject any path containing an encoded dot, slash or backslash.
+    Reject,
+    /// Decode encoded dots, slashes and backslashes before normalization.
+    ///
+    /// Encoded slashes and backslashes are treated as segment separators.
+    Decode,
+}
+
+/// A path normalizer, used to remove traversal components from paths.
+#[derive(Clone, Debug)]
+pub struct Normalizer {
+    encoded: EncodedPolicy,
+    #[cfg(feature = "unicode-normalization")]
+    nfc: bool,
+}
+
+impl Default for Normalizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Normalizer {
+    /// Creates a new `Normalizer` which rejects encoded dots and slashes.
+    pub fn new() -> Self {
+        Self {
+            encoded: EncodedPolicy::Reject,
+            #[cfg(feature = "unicode-normalization")]
+            nfc: false,
+        }
+    }
+
+    /// Sets the policy used for encoded dots and slashes.
+    pub fn encoded(mut self, policy: EncodedPolicy) -> Self {
+        self.encoded = policy;
+        self
+    }
+
+    /// Enables or disables Unicode normalization into NFC.
+    ///
+    /// Note that this only applies to characters which are present in the path
+    /// as-is; percent-encoded characters are not decoded for normalization.
+    ///
+    /// This is available via the `"unicode-normalization"` Cargo feature.
+    #[cfg(feature = "unicode-normalization")]
+    pub fn nfc(mut self, enabled: bool) -> Self {
+        self.nfc = enabled;
+        self
+    }
+
+    /// Normalizes a path, resolving any dot segments.
+    ///
+    /// The returned path always starts with a `/` and contains no empty, `.`
+    /// or `..` segments. Any `..` segment which would move above the root of
+    /// the path is dropped, as described in RFC 3986. If the path is already
+    /// normalized, it's returned without any allocation.
+    pub fn normalize<'p>(&self, path: &'p str) -> Result<Cow<'p, str>, NormalizeError> {
+        let decoded = self.decode(path)?;
+
+        #[cfg(feature = "unicode-normalization")]
+        let decoded = self.compose(decoded);
+
+        let mut segments: Vec<&str> = Vec::new();
+        for segment in decoded.split('/') {
+            match segment {
+                "" | "." => (),
+                ".." => {
+                    segments.pop();
+                }
+                segment => segments.push(segment),
+            }
+        }
+
+        let normalized = format!("/{}", segments.join("/"));
+
+        if normalized == path {
+            return Ok(Cow::Borrowed(path));
+        }
+
+        Ok(Cow::Owned(normalized))
+    }
+
+    /// Handles any encoded dots and slashes based on the configured policy.
+    fn decode<'p>(&self, path: &'p str) -> Result<Cow<'p, str>, NormalizeError> {
+        let bytes = path.as_bytes();
+        let mut output: Option<String> = None;
+        let mut last = 0;
+        let mut idx = 0;
+
+        while idx < bytes.len() {
+            let decoded = match encoded_byte(bytes, idx) {
+                Some(b'.') => '.',
+                Some(b'/') => '/',
+                Some(b'\\') => '/',
+                _ => {
+                    idx += 1;
+                    continue;
+                }
+            };
+
+            if self.encoded == EncodedPolicy::Reject {
+                return Err(match decoded {
+                    '.' => NormalizeError::EncodedDot,
+                    _ => NormalizeError::EncodedSlash,
+                });
+            }
+
+            let output = output.get_or_insert_with(|| String::with_capacity(path.len()));
+            output.push_str(&path[last..idx]);
+            output.push(decoded);
+
+            idx += 3;
+            last = idx;
+        }
+
+        Ok(match output {
+            None => Cow::Borrowed(path),
+            Some(mut output) => {
+                output.push_str(&path[last..]);
+                Cow::Owned(output)
+            }
+        })
+    }
+
+    /// Applies Unicode normalization into NFC, if enabled.
+    #[cfg(feature = "unicode-normalization")]
+    fn compose<'p>(&self, path: Cow<'p, str>) -> Cow<'p, str> {
+        use unicode_normalization::{is_nfc, UnicodeNormalization};
+
+        if !self.nfc || is_nfc(&path) {
+            return path;
+        }
+
+        Cow::Owned(path.nfc().collect())
+    }
+}
+
+/// Error type returned when a path is rejected during normalization.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NormalizeError {
+    /// The path contains an encoded dot (`%2E`).
+    EncodedDot,
+    /// The path contains an encoded slash or backslash (`%2F` or `%5C`).
+    EncodedSlash,
+}
+
+impl fmt::Display for NormalizeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NormalizeError::EncodedDot => write!(f, "path contains an encoded dot"),
+            NormalizeError::EncodedSlash => write!(f, "path contains an encoded slash"),
+        }
+    }
+}
+
+impl Error for NormalizeError {}
+
+/// Percent-encodes a value so that it can be used as a single path segment.
+///
+/// Any byte which is not valid within a path segment is encoded, as is any `/`
+/// or `%` character, so that the encoded value is routed as one segment and can
+/// be recovered exactly via `decode_segment`.
+pub fn encode_segment(value: &str) -> Cow<'_, str> {
+    let plain = |b: u8| b.is_ascii_alphanumeric() || b"-._~!$&'()*+,;=:@".contains(&b);
+
+    if value.bytes().all(plain) {
+        return Cow::Borrowed(value);
+    }
+
+    let mut output = String::with_capacity(value.len() * 3);
+
+    for b in value.bytes() {
+        if plain(b) {
+            output.push(b as char);
+        } else {
+            output.push_str(&format!("%{:02X}", b));
+        }
+    }
+
+    Cow::Owned(output)
+}
+
+/// Decodes a percent-encoded path segment, such as those from `encode_segment`.
+///
+/// If the segment contains an invalid escape, or decodes into a value which is
+/// not valid UTF-8, a `None` value is returned.
+pub fn decode_segment(segment: &str) -> Option<Cow<'_, str>> {
+    if !segment.contains('%') {
+        return Some(Cow::Borrowed(segment));
+    }
+
+    let bytes = segment.as_bytes();
+    let mut output = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+
+    while idx < bytes.len() {
+        if bytes[idx] != b'%' {
+            output.push(bytes[idx]);
+            idx += 1;
+            continue;
+        }
+
+        output.push(encoded_byte(bytes, idx)?);
+        idx += 3;
+    }
+
+    String::from_utf8(output).ok().map(Cow::Owned)
+}
+
+/// Decodes a percent-encoded byte at the provided index, if there is one.
+fn encoded_byte(bytes: &[u8], idx: usize) -> Option<u8> {
+    if bytes[idx] != b'%' || idx + 2 >= bytes.len() {
+        return None;
+    }
+
+    let hex = |b: u8| (b as char).to_digit(16);
+    let high = hex(bytes[idx + 1])?;
+    let low = hex(bytes[idx + 2])?;
+
+    Some((high * 16 + low) as u8)
+}
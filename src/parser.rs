@@ -4,7 +4,8 @@
 //! at tree creation time, to specify priority order when routing an
 //! incoming set of segments. A parser can also be a pure function which
 //! can derive a potential `Matcher` from an input segment directly.
-use crate::matcher::{DynamicMatcher, Matcher, StaticMatcher};
+use crate::matcher::{ConstrainedMatcher, DynamicMatcher, Matcher, StaticMatcher, WildcardMatcher};
+use crate::pattern::Segment;
 
 /// Parsing trait to enable conversion from literals into matchers.
 ///
@@ -36,8 +37,16 @@ impl Parser for StaticParser {
     ///
     /// Note that although this returns a result, it will never fail
     /// as every string literal can be treated as a static matcher.
+    ///
+    /// A segment escaped as a literal (such as `\:name`, as written by a
+    /// `RoutePattern`) is unescaped, and so will match the segment `:name`.
+    /// Only a leading `\` followed by `:`, `*` or `\` is an escape, and any
+    /// other segment is matched exactly as written.
     fn parse(&self, segment: &str) -> Option<Box<dyn Matcher>> {
-        Some(Box::new(StaticMatcher::new(segment)))
+        match Segment::parse(segment) {
+            Ok(Segment::Literal(literal)) => Some(Box::new(StaticMatcher::new(literal))),
+            _ => Some(Box::new(StaticMatcher::new(segment))),
+        }
     }
 }
 
@@ -45,18 +54,23 @@ impl Parser for StaticParser {
 pub struct DynamicParser;
 
 impl Parser for DynamicParser {
-    /// Parses out a dynamic segment based on the `:.+` syntax.
+    /// Parses out a dynamic segment based on the syntax of a `RoutePattern`.
+    ///
+    /// Parameters (`:name`) create a `DynamicMatcher`, constrained parameters
+    /// (`:name(constraint)`) create a `ConstrainedMatcher`, and wildcards
+    /// (`*name`) create a `WildcardMatcher`. Any other segment is left to the
+    /// following parsers, including those which are not valid syntax.
     ///
     /// If you wish to use a custom syntax, you can construct a custom `Parser`
     /// implementation which constructs a `DynamicMatcher` instance.
     fn parse(&self, segment: &str) -> Option<Box<dyn Matcher>> {
-        if &segment[0..1] != ":" || segment.len() == 1 {
-            return None;
+        match Segment::parse(segment).ok()? {
+            Segment::Literal(_) => None,
+            Segment::Parameter(name) => Some(Box::new(DynamicMatcher::new(name))),
+            Segment::Constrained { name, constraint } => {
+                Some(Box::new(ConstrainedMatcher::new(name, &constraint)?))
+            }
+            Segment::Wildcard(name) => Some(Box::new(WildcardMatcher::new(name))),
         }
-
-        let field = &segment[1..];
-        let matcher = DynamicMatcher::new(field);
-
-        Some(Box::new(matcher))
     }
 }
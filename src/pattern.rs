@@ -0,0 +1,153 @@
+//! Route patterns parsed into typed segments.
+//!
+//! A `RoutePattern` represents a route such as `/users/:id` which has been
+//! parsed and validated ahead of time. Patterns can be used anywhere a route
+//! is accepted as a string (as they implement `AsRef<str>`), and are written
+//! back out in a normalized form via `Display`.
+//!
+//! The syntax of each segment is as follows:
+//!
+//! - `:name` is a parameter, capturing any value.
+//! - `:name(constraint)` is a parameter which only captures values matching the
+//!   constraint, which is a regular expression matched against the entire value.
+//! - `*name` is a wildcard, capturing the rest of the path. It may only appear as
+//!   the final segment of a pattern.
+//! - Anything else is a literal. A literal which would otherwise start with `:`,
+//!   `*` or `\` is escaped with a leading `\` (i.e. `\:name` is the literal `:name`).
+//!
+//! Empty segments are removed, as they're also ignored by a `Router`. Each kind
+//! of segment is given meaning by the `DynamicParser` and `StaticParser`, so a
+//! router using both parsers will route a pattern as it's written.
+//!
+//! This grammar is shared with the `router!` macro and the `Route` derive, so
+//! any pattern accepted by one is accepted by the other.
+use std::fmt;
+use std::str::FromStr;
+
+use crate::capture::Params;
+
+pub use usher_pattern::{PatternError, Segment};
+
+/// A route pattern which has been parsed into segments.
+///
+/// Patterns retrieved from a `Router` (such as via `Router::routes`) use the
+/// delimiter of the router, and so a router which doesn't split on `/` will
+/// provide patterns such as `:sub.example.com` (which cannot be parsed back).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RoutePattern {
+    raw: String,
+    segments: Vec<Segment>,
+    delimiter: char,
+}
+
+impl RoutePattern {
+    /// Parses a route pattern from a string.
+    pub fn parse(pattern: &str) -> Result<Self, PatternError> {
+        Ok(Self::from_segments(usher_pattern::parse(pattern)?, '/'))
+    }
+
+    /// Creates a pattern from the literal segments of the nodes of a route.
+    ///
+    /// The segments are expected in the order they're written, and any segment
+    /// which is not valid syntax is treated as a literal (as that's how it will
+    /// have been handled by the `DynamicParser` and `StaticParser`).
+    pub(crate) fn from_raw(segments: Vec<&str>, delimiter: char) -> Self {
+        let segments = segments
+            .into_iter()
+            .map(|raw| Segment::parse(raw).unwrap_or_else(|_| Segment::Literal(raw.to_owned())))
+            .collect();
+
+        Self::from_segments(segments, delimiter)
+    }
+
+    /// Creates a pattern from segments, formatting them with a delimiter.
+    fn from_segments(segments: Vec<Segment>, delimiter: char) -> Self {
+        let raw = segments
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(&delimiter.to_string());
+
+        Self {
+            raw: if delimiter == '/' {
+                format!("/{}", raw)
+            } else {
+                raw
+            },
+            segments,
+            delimiter,
+        }
+    }
+
+    /// Retrieves the segments of this pattern.
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Retrieves the normalized pattern as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.raw
+    }
+
+    /// Retrieves the names of all parameters in this pattern, in order.
+    pub fn params(&self) -> impl Iterator<Item = &str> {
+        self.segments.iter().filter_map(Segment::name)
+    }
+
+    /// Creates a path from this pattern, using the provided parameters.
+    ///
+    /// This is the reverse of routing; each parameter is replaced with the value
+    /// of the parameter with the same name. If any parameter is missing, or a
+    /// value is empty, a `None` value is returned. The same applies when a value
+    /// would span several segments (unless used for a wildcard), or would not
+    /// satisfy the constraint of a constrained parameter.
+    pub fn reverse(&self, params: &Params) -> Option<String> {
+        let mut values = Vec::with_capacity(self.segments.len());
+
+        for segment in &self.segments {
+            let value = match segment {
+                Segment::Literal(literal) => literal,
+                Segment::Wildcard(name) => params.get(name).filter(|value| !value.is_empty())?,
+                _ => {
+                    let value = params.get(segment.name()?)?;
+                    if value.is_empty()
+                        || value.contains(self.delimiter)
+                        || !segment.is_match(value)
+                    {
+                        return None;
+                    }
+                    value
+                }
+            };
+            values.push(value);
+        }
+
+        let path = values.join(&self.delimiter.to_string());
+
+        Some(if self.delimiter == '/' {
+            format!("/{}", path)
+        } else {
+            path
+        })
+    }
+}
+
+impl AsRef<str> for RoutePattern {
+    fn as_ref(&self) -> &str {
+        &self.raw
+    }
+}
+
+impl fmt::Display for RoutePattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+impl FromStr for RoutePattern {
+    type Err = PatternError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
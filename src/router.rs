@@ -6,10 +6,16 @@
 //! a generic tree structure). The structures in this module can be used
 //! directly, but would typically provide more value as the underlying
 //! routing for more domain oriented structures.
-use crate::capture::Captures;
+use crate::capture::{Captures, OwnedCaptures};
 use crate::matcher::Matcher;
 use crate::node::Node;
 use crate::parser::Parser;
+use crate::pattern::RoutePattern;
+
+use std::error::Error;
+use std::fmt;
+use std::str::Split;
+use std::sync::Arc;
 
 /// Routing structure providing routing for generic types.
 ///
@@ -22,36 +28,93 @@ use crate::parser::Parser;
 /// they're checked against a path segment. If a parser matching any segment is
 /// placed first, it will always match and short circuit before checking any other
 /// provided parsers. Always put the "strictest" parsers first in the vector.
+///
+/// By default a `Router` splits paths on `/` and walks the segments from left to
+/// right, as with filesystem paths. Other delimiters and directions are available
+/// via `Router::with_delimiter`, which allows routing things like domain labels.
+///
+/// When routing untrusted input, resource limits can be applied to both lookup
+/// and registration via `Router::limits`; see `Limits` for further details.
+///
+/// A `Router` can be cloned whenever the inner value type can be cloned. The
+/// parsers and matchers are shared between clones, whereas the structure of the
+/// tree (and every value within it) is deep copied.
+#[derive(Clone)]
 pub struct Router<T> {
     root: Node<T>,
-    parsers: Vec<Box<dyn Parser>>,
+    parsers: Arc<[Box<dyn Parser>]>,
+    delimiter: char,
+    reversed: bool,
+    limits: Limits,
 }
 
 impl<T> Router<T> {
     /// Creates a new `Router`, using the provided matchers.
-    pub fn new(mut parsers: Vec<Box<dyn Parser>>) -> Self {
-        parsers.shrink_to_fit();
+    pub fn new(parsers: Vec<Box<dyn Parser>>) -> Self {
+        Self::with_delimiter(parsers, '/', false)
+    }
 
+    /// Creates a new `Router` which splits paths on a custom delimiter.
+    ///
+    /// If `reversed` is set, segments are walked from right to left rather than
+    /// from left to right. As an example, a router with a delimiter of `.` which
+    /// is reversed can be used to route domain names by their labels, such that
+    /// `api.example.com` is routed through `com`, `example` and then `api`. This
+    /// applies to both insertion and lookup, so paths are always provided in the
+    /// their natural order. Capture bounds always refer to the provided path.
+    pub fn with_delimiter(parsers: Vec<Box<dyn Parser>>, delimiter: char, reversed: bool) -> Self {
         let parsed = parse_segment(&parsers, "/");
         let parsed = parsed.expect("unparsed segment");
 
         Self {
-            parsers,
-            root: Node::new(parsed),
+            delimiter,
+            reversed,
+            parsers: parsers.into(),
+            root: Node::new("/", parsed),
+            limits: Limits::new(),
         }
     }
 
+    /// Sets the resource limits applied to lookup and registration.
+    ///
+    /// Limits apply to all future operations on the router; existing routes are
+    /// not checked against the limits. By default a router has no limits.
+    pub fn limits(&mut self, limits: Limits) {
+        self.limits = limits;
+    }
+
     /// Inserts a route/handler pair for the provided path and method.
     ///
     /// Internally this is pretty similar to `update`, except that it guarantees
     /// that the provided value `t` is stored as the leaf value. If the leaf already
     /// contains a value, it will be overwritten. If this is not desired, you can
     /// likely implement the insertion easily via `update` instead.
+    ///
+    /// The path can be provided as either a string or a parsed `RoutePattern`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the path exceeds the depth limit of the router; see `try_insert`.
     #[inline(always)]
-    pub fn insert(&mut self, path: &str, t: T) {
+    pub fn insert<P>(&mut self, path: P, t: T)
+    where
+        P: AsRef<str>,
+    {
         self.update(path, |_| t)
     }
 
+    /// Attempts to insert a route/handler pair for the provided path.
+    ///
+    /// This is the same as `insert`, except that a path exceeding the depth limit
+    /// of the router results in a `LimitError` rather than a panic.
+    #[inline(always)]
+    pub fn try_insert<P>(&mut self, path: P, t: T) -> Result<(), LimitError>
+    where
+        P: AsRef<str>,
+    {
+        self.try_update(path, |_| t)
+    }
+
     /// Attempts to route a path to a leaf value.
     ///
     /// This function will also capture any parameters involved in routing, into a
@@ -69,29 +132,385 @@ impl<T> Router<T> {
     /// If a route does not require any parameters, this vector is still returned but
     /// is empty. This isn't a big deal; a `Vec` will only allocate memory when you
     /// first push something into it in most cases, so the performance hit is minimal.
-    pub fn lookup<'a>(&'a self, path: &str) -> Option<(&T, Captures<'a>)> {
-        let offset = path.as_ptr() as usize;
-        let mut current = &self.root;
+    ///
+    /// If the path exceeds any of the limits of the router, it's treated as a path
+    /// which does not match. Use `try_lookup` to distinguish between the two cases.
+    #[inline]
+    pub fn lookup<'a>(&'a self, path: &str) -> Option<(&'a T, Captures<'a>)> {
+        self.try_lookup(path).ok().flatten()
+    }
+
+    /// Attempts to route a path to a leaf value, enforcing the router limits.
+    ///
+    /// This is the same as `lookup`, except that a path exceeding any of the limits
+    /// of the router results in a `LimitError`. Walking stops as soon as a limit is
+    /// exceeded, so the work done for any path is bounded by the limits.
+    pub fn try_lookup<'a>(
+        &'a self,
+        path: &str,
+    ) -> Result<Option<(&'a T, Captures<'a>)>, LimitError> {
         let mut captures = Vec::new();
+        let current = self.walk(path, &mut captures, |_, _, _| ())?;
 
-        for segment in path.split('/').filter(|s| !s.is_empty()) {
-            current = current
-                .children()
-                .iter()
-                .find(|child| child.matcher().is_match(segment))?;
+        Ok(current
+            .and_then(Node::value)
+            .map(|handler| (handler, captures)))
+    }
 
-            let matcher = current.matcher();
-            let capture = matcher.capture(segment);
+    /// Attempts to route a path to a leaf value, with owned captures.
+    ///
+    /// This is the same as `lookup`, except that the captures are returned as
+    /// `OwnedCaptures`. Capture names are interned by the nodes of the router on
+    /// their first capture, so every later lookup shares the same allocations
+    /// (as do clones of the router made afterward) without allocating a name.
+    #[inline]
+    pub fn lookup_owned(&self, path: &str) -> Option<(&T, OwnedCaptures)> {
+        self.try_lookup_owned(path).ok().flatten()
+    }
 
-            if let Some((name, (start, end))) = capture {
-                let ptr = segment.as_ptr() as usize - offset;
-                let val = (ptr + start, ptr + end);
+    /// Attempts to route a path to a leaf value, with owned captures.
+    ///
+    /// This is the same as `lookup_owned`, except that a path exceeding any of
+    /// the limits of the router results in a `LimitError` rather than no match.
+    pub fn try_lookup_owned(&self, path: &str) -> Result<Option<(&T, OwnedCaptures)>, LimitError> {
+        let mut captures = Vec::new();
+        let mut nodes = Vec::new();
 
-                captures.push((name, val));
+        let current = self.walk(path, &mut captures, |node, _, count| {
+            // a matcher captures at most once, so the node made the last capture
+            if count > nodes.len() {
+                nodes.push(node);
+            }
+        })?;
+
+        let value = match current.and_then(Node::value) {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+
+        let mut owned = OwnedCaptures::new();
+        for ((name, (start, end)), node) in captures.iter().zip(nodes) {
+            owned.push(node.name(name), *start..*end);
+        }
+
+        Ok(Some((value, owned)))
+    }
+
+    /// Attempts to route a path to a leaf value, along with any inherited values.
+    ///
+    /// This behaves the same as `lookup`, except that the values of any nodes
+    /// passed through on the way to the leaf are also returned. The values are
+    /// ordered from the root of the tree, with the last value always being the
+    /// leaf value (so the returned vector is never empty). This allows storing
+    /// values at a node such as `/admin` which apply to every route below it.
+    ///
+    /// Much like `lookup`, a path will only match if the leaf node has a value;
+    /// the presence of a value on any ancestor node is not enough for a match.
+    pub fn lookup_inherited<'a>(&'a self, path: &str) -> Option<(Vec<&'a T>, Captures<'a>)> {
+        let mut values = Vec::new();
+        let mut captures = Vec::new();
+
+        let current = self.walk(path, &mut captures, |node, _, _| {
+            if let Some(value) = node.value() {
+                values.push(value);
+            }
+        });
+
+        current.ok()??.value().map(|_| (values, captures))
+    }
+
+    /// Retrieves the route pattern which a path would be routed to.
+    ///
+    /// Rather than the path itself, this returns the pattern of the route as it
+    /// was registered (i.e. `/users/:id` rather than `/users/123`), which can be
+    /// useful for things like logging and metrics. Much like `lookup`, a pattern
+    /// will only be returned if the path routes to a node which has a value.
+    pub fn matched_route(&self, path: &str) -> Option<RoutePattern> {
+        let mut segments = Vec::new();
+
+        self.try_lookup_segments(path, &mut segments).ok()??;
+
+        Some(self.pattern(segments))
+    }
+
+    /// Routes a path to a leaf value, collecting the segments of matched nodes.
+    ///
+    /// This is the same as `try_lookup`, except that the segment of each node
+    /// walked (excluding the root) is pushed into the provided vector, so that
+    /// the matched route can be formatted via `pattern` without a second walk.
+    pub(crate) fn try_lookup_segments<'a>(
+        &'a self,
+        path: &str,
+        segments: &mut Vec<&'a str>,
+    ) -> Result<Option<(&'a T, Captures<'a>)>, LimitError> {
+        let mut captures = Vec::new();
+        let current = self.walk(path, &mut captures, |node, _, _| {
+            if !std::ptr::eq(node, &self.root) {
+                segments.push(node.segment());
+            }
+        })?;
+
+        Ok(current
+            .and_then(Node::value)
+            .map(|handler| (handler, captures)))
+    }
+
+    /// Attempts to route the longest matching prefix of a path to a value.
+    ///
+    /// Rather than requiring the entire path to match, this will route to the
+    /// deepest node along the path which contains a value, even if the rest of
+    /// the path does not match anything in the tree. This is useful for cases
+    /// such as mounting sub-applications, or proxying the tail of a path.
+    ///
+    /// Alongside the value and any captures found up to the matched node, this
+    /// returns the byte offset at which the unmatched remainder of the path begins
+    /// (with any leading delimiters skipped). The remainder can be retrieved via
+    /// `&path[offset..]`; if the entire path matched, the remainder will be empty.
+    ///
+    /// In the case of a reversed router, the unmatched remainder sits at the start
+    /// of the path rather than the end, and so the offset is instead the position
+    /// at which the remainder ends (and it can be retrieved via `&path[..offset]`).
+    pub fn lookup_prefix<'a>(&'a self, path: &str) -> Option<(&'a T, Captures<'a>, usize)> {
+        let mut deepest = None;
+        let mut captures = Vec::new();
+
+        let walked = self.walk(path, &mut captures, |node, boundary, count| {
+            if let Some(value) = node.value() {
+                deepest = Some((value, count, boundary));
             }
+        });
+
+        walked.ok()?;
+        deepest.map(|(value, count, boundary)| {
+            let offset = if self.reversed {
+                path[..boundary].trim_end_matches(self.delimiter).len()
+            } else {
+                let rest = &path[boundary..];
+                boundary + rest.len() - rest.trim_start_matches(self.delimiter).len()
+            };
+
+            captures.truncate(count);
+
+            (value, captures, offset)
+        })
+    }
+
+    /// Visits every value stored in the router.
+    ///
+    /// Values are visited in depth-first order, with each node being visited
+    /// before any of its children (in the order they were inserted).
+    pub fn for_each<'a, F>(&'a self, mut f: F)
+    where
+        F: FnMut(&'a T),
+    {
+        fn visit<'a, T, F: FnMut(&'a T)>(node: &'a Node<T>, f: &mut F) {
+            if let Some(value) = node.value() {
+                f(value);
+            }
+            for child in node.children() {
+                visit(child, f);
+            }
+        }
+        visit(&self.root, &mut f)
+    }
+
+    /// Retrieves every route pattern stored in the router, alongside its value.
+    ///
+    /// Routes are returned in the same order they're visited by `for_each`, and
+    /// each pattern is formatted the same way as those returned by `matched_route`.
+    pub fn routes(&self) -> Vec<(RoutePattern, &T)> {
+        let mut routes = Vec::new();
+        self.visit(|nodes, value| {
+            let segments = nodes.iter().map(|node| node.segment()).collect();
+            routes.push((self.pattern(segments), value));
+        });
+        routes
+    }
+
+    /// Visits every value stored in the router, alongside the nodes of its path.
+    ///
+    /// The nodes are provided in path order, and do not include the root node.
+    pub(crate) fn visit<'a, F>(&'a self, mut f: F)
+    where
+        F: FnMut(&[&'a Node<T>], &'a T),
+    {
+        fn visit_node<'a, T, F>(node: &'a Node<T>, nodes: &mut Vec<&'a Node<T>>, f: &mut F)
+        where
+            F: FnMut(&[&'a Node<T>], &'a T),
+        {
+            if let Some(value) = node.value() {
+                f(nodes, value);
+            }
+            for child in node.children() {
+                nodes.push(child);
+                visit_node(child, nodes, f);
+                nodes.pop();
+            }
+        }
+
+        visit_node(&self.root, &mut Vec::new(), &mut f);
+    }
+
+    /// Converts this router into a router of another value type.
+    ///
+    /// The structure of the tree is retained as-is, and every value stored in
+    /// the tree is passed through the provided function to create the value of
+    /// the new router. This makes it possible to build a router of simple values
+    /// and then compile them into something more complex at a later point.
+    pub fn map<U, F>(self, mut f: F) -> Router<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        Router {
+            root: self.root.map(&mut f),
+            parsers: self.parsers,
+            delimiter: self.delimiter,
+            reversed: self.reversed,
+            limits: self.limits,
+        }
+    }
+
+    /// Creates a router of another value type from a reference to this router.
+    ///
+    /// This is the same as `map` except that this router is left untouched, so
+    /// the structure of the tree is cloned to the new router (sharing matchers).
+    pub fn map_ref<U, F>(&self, mut f: F) -> Router<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Router {
+            root: self.root.map_ref(&mut f),
+            parsers: self.parsers.clone(),
+            delimiter: self.delimiter,
+            reversed: self.reversed,
+            limits: self.limits,
+        }
+    }
+
+    /// Attempts to convert this router into a router of another value type.
+    ///
+    /// This is the same as `map` except that the conversion can fail; the first
+    /// error returned by the provided function will be returned to the caller.
+    pub fn try_map<U, E, F>(self, mut f: F) -> Result<Router<U>, E>
+    where
+        F: FnMut(T) -> Result<U, E>,
+    {
+        Ok(Router {
+            root: self.root.try_map(&mut f)?,
+            parsers: self.parsers,
+            delimiter: self.delimiter,
+            reversed: self.reversed,
+            limits: self.limits,
+        })
+    }
+
+    /// Mounts another `Router` underneath a prefix of this router.
+    ///
+    /// The nodes of the provided router are moved underneath the node found at
+    /// `prefix`, which will be built out if it does not already exist. Wherever
+    /// the two trees overlap, the children are merged together using the same
+    /// segment matching as `update`. Any segments leading to the prefix are
+    /// parsed using the parsers of this router; mounted nodes keep the matchers
+    /// they were originally constructed with.
+    ///
+    /// If both trees contain a value at the same location, the provided `Conflict`
+    /// policy is used to decide which value is kept. When using `Conflict::Error`
+    /// the trees are checked before any changes are made, so a failed mount will
+    /// leave this router untouched.
+    ///
+    /// Mounted routes are also checked against the depth limit of this router (as
+    /// their depth includes the prefix), regardless of the `Conflict` policy. If a
+    /// route is too deep, the mount fails with a `MountError` carrying the limit.
+    ///
+    /// The provided router must split paths the same way as this router; if the
+    /// delimiter or direction differ, the mount fails without any changes made.
+    pub fn mount(
+        &mut self,
+        prefix: &str,
+        other: Router<T>,
+        conflict: Conflict,
+    ) -> Result<(), MountError> {
+        self.mount_with(
+            prefix,
+            other,
+            conflict,
+            |_, _| true,
+            |existing, incoming| {
+                if conflict != Conflict::Keep {
+                    *existing = incoming;
+                }
+            },
+        )
+    }
+
+    /// Mounts another `Router` underneath a prefix, merging values with a function.
+    ///
+    /// This is the same as `mount`, except that values found at the same location
+    /// are only treated as a conflict if `conflicts` returns `true`, and are then
+    /// combined using `merge` (which is responsible for applying the policy).
+    pub(crate) fn mount_with<C, M>(
+        &mut self,
+        prefix: &str,
+        other: Router<T>,
+        conflict: Conflict,
+        conflicts: C,
+        mut merge: M,
+    ) -> Result<(), MountError>
+    where
+        C: Fn(&T, &T) -> bool,
+        M: FnMut(&mut T, T),
+    {
+        if other.delimiter != self.delimiter || other.reversed != self.reversed {
+            return Err(MountError {
+                path: prefix.to_owned(),
+                kind: MountErrorKind::Incompatible,
+            });
         }
 
-        current.value().map(|handler| (handler, captures))
+        if let Some(limit) = self.limits.depth {
+            let mut path = String::new();
+
+            for segment in self.segments(prefix) {
+                path.push('/');
+                path.push_str(segment);
+            }
+
+            let depth = self.segments(prefix).count();
+
+            if let Some(path) = find_too_deep(&other.root, depth, limit, path) {
+                return Err(MountError {
+                    path,
+                    kind: MountErrorKind::Limit(LimitError::Depth(limit)),
+                });
+            }
+        }
+
+        if conflict == Conflict::Error {
+            let mut path = String::new();
+            let mut current = Some(&self.root);
+
+            for segment in self.segments(prefix) {
+                path.push('/');
+                path.push_str(segment);
+
+                current = current
+                    .and_then(|node| node.children().iter().find(|child| child.accepts(segment)));
+            }
+
+            if let Some(current) = current {
+                if let Some(path) = find_conflict(current, &other.root, path, &conflicts) {
+                    return Err(MountError {
+                        path,
+                        kind: MountErrorKind::Conflict,
+                    });
+                }
+            }
+        }
+
+        let current = self.node_mut(prefix);
+        merge_node(current, other.root, &mut merge);
+
+        Ok(())
     }
 
     /// Updates a leaf node inside a `Router`.
@@ -99,22 +518,190 @@ impl<T> Router<T> {
     /// If the node does not currently exist, it will be built out and populated
     /// with the result of the update function (which can be used to generate a
     /// value for first insertion).
-    pub fn update<F>(&mut self, path: &str, f: F)
+    ///
+    /// # Panics
+    ///
+    /// Panics if the path exceeds the depth limit of the router; see `try_update`.
+    pub fn update<P, F>(&mut self, path: P, f: F)
+    where
+        P: AsRef<str>,
+        F: FnOnce(Option<T>) -> T,
+    {
+        if let Err(err) = self.try_update(path, f) {
+            panic!("{}", err);
+        }
+    }
+
+    /// Attempts to update a leaf node inside a `Router`.
+    ///
+    /// This is the same as `update`, except that a path exceeding the depth limit
+    /// of the router results in a `LimitError` rather than a panic. In this case
+    /// the router is left untouched, and the update function is never called.
+    pub fn try_update<P, F>(&mut self, path: P, f: F) -> Result<(), LimitError>
     where
+        P: AsRef<str>,
         F: FnOnce(Option<T>) -> T,
     {
+        let path = path.as_ref();
+
+        if let Some(limit) = self.limits.depth {
+            if self.segments(path).count() > limit {
+                return Err(LimitError::Depth(limit));
+            }
+        }
+
+        self.node_mut(path).update(f);
+        Ok(())
+    }
+
+    /// Creates a route pattern from the segments of the nodes along a path.
+    ///
+    /// The segments are expected in the order they're walked, excluding the root.
+    pub(crate) fn pattern(&self, mut segments: Vec<&str>) -> RoutePattern {
+        if self.reversed {
+            segments.reverse();
+        }
+
+        RoutePattern::from_raw(segments, self.delimiter)
+    }
+
+    /// Walks the tree along a path, returning the node at the end of the path.
+    ///
+    /// Every node matched along the path (including the root) is passed to the
+    /// provided visitor along with the offset of the boundary between the matched
+    /// and unmatched parts of the path (which is the end of the matched segment,
+    /// or the start in the case of a reversed router), and the number of captures
+    /// found up to and including the node. Captures
+    /// are pushed into the provided vector as they're found. If any segment of
+    /// the path fails to match, a `None` value is returned. If the path exceeds
+    /// any of the limits of the router, walking stops and an error is returned.
+    ///
+    /// A node with a wildcard matcher is matched against the rest of the path,
+    /// rather than a single segment, and walking ends once it has been matched.
+    fn walk<'a, F>(
+        &'a self,
+        path: &str,
+        captures: &mut Captures<'a>,
+        mut visit: F,
+    ) -> Result<Option<&'a Node<T>>, LimitError>
+    where
+        F: FnMut(&'a Node<T>, usize, usize),
+    {
+        let limits = &self.limits;
+
+        if let Some(limit) = limits.path_bytes {
+            if path.len() > limit {
+                return Err(LimitError::PathBytes(limit));
+            }
+        }
+
+        let offset = path.as_ptr() as usize;
+        let mut current = &self.root;
+        let mut steps = 0;
+
+        visit(current, if self.reversed { path.len() } else { 0 }, 0);
+
+        for (idx, segment) in self.segments(path).enumerate() {
+            if let Some(limit) = limits.segments {
+                if idx >= limit {
+                    return Err(LimitError::Segments(limit));
+                }
+            }
+
+            let mut found = None;
+            for child in current.children() {
+                steps += 1;
+                if let Some(limit) = limits.steps {
+                    if steps > limit {
+                        return Err(LimitError::Steps(limit));
+                    }
+                }
+                let input = if child.matcher().is_wildcard() {
+                    self.remainder(path, segment)
+                } else {
+                    segment
+                };
+                if child.matcher().is_match(input) {
+                    found = Some((child, input));
+                    break;
+                }
+            }
+
+            let (segment, wildcard) = match found {
+                Some((found, input)) => {
+                    current = found;
+                    (input, found.matcher().is_wildcard())
+                }
+                None => return Ok(None),
+            };
+
+            if let Some(limit) = limits.segments {
+                if wildcard && idx + self.segments(segment).count() > limit {
+                    return Err(LimitError::Segments(limit));
+                }
+            }
+
+            let matcher = current.matcher();
+            let capture = matcher.capture(segment);
+            let ptr = segment.as_ptr() as usize - offset;
+
+            if let Some((name, (start, end))) = capture {
+                let val = (ptr + start, ptr + end);
+
+                captures.push((name, val));
+
+                if let Some(limit) = limits.captures {
+                    if captures.len() > limit {
+                        return Err(LimitError::Captures(limit));
+                    }
+                }
+            }
+
+            let boundary = if self.reversed {
+                ptr
+            } else {
+                ptr + segment.len()
+            };
+
+            visit(current, boundary, captures.len());
+
+            if wildcard {
+                break;
+            }
+        }
+
+        Ok(Some(current))
+    }
+
+    /// Retrieves the rest of a path, starting from a segment within the path.
+    ///
+    /// Any delimiters trailing the rest of the path (or leading it, in the case
+    /// of a reversed router) are excluded from the remainder.
+    fn remainder<'p>(&self, path: &'p str, segment: &'p str) -> &'p str {
+        let start = segment.as_ptr() as usize - path.as_ptr() as usize;
+
+        if self.reversed {
+            path[..start + segment.len()].trim_start_matches(self.delimiter)
+        } else {
+            path[start..].trim_end_matches(self.delimiter)
+        }
+    }
+
+    /// Retrieves a mutable node inside a `Router`, building it out if needed.
+    fn node_mut(&mut self, path: &str) -> &mut Node<T> {
+        let segments = self.segments(path);
         let mut current = &mut self.root;
 
-        for segment in path.split('/').filter(|s| !s.is_empty()) {
+        for segment in segments {
             let child = current
                 .children()
                 .iter()
-                .find(|child| child.matcher().is_match(segment));
+                .find(|child| child.accepts(segment));
 
             if child.is_none() {
                 let parsed = parse_segment(&self.parsers, segment);
                 let parsed = parsed.expect("unparsed segment");
-                let router = Node::new(parsed);
+                let router = Node::new(segment, parsed);
 
                 current.add_child(router);
             }
@@ -122,11 +709,285 @@ impl<T> Router<T> {
             current = current
                 .children_mut()
                 .iter_mut()
-                .find(|child| child.matcher().is_match(segment))
+                .find(|child| child.accepts(segment))
                 .unwrap();
         }
 
-        current.update(f);
+        current
+    }
+
+    /// Splits a path into segments based on the configuration of this router.
+    fn segments<'p>(&self, path: &'p str) -> Segments<'p> {
+        Segments {
+            inner: path.split(self.delimiter),
+            reversed: self.reversed,
+        }
+    }
+}
+
+/// Iterator over the non-empty segments of a path, in routing order.
+struct Segments<'p> {
+    inner: Split<'p, char>,
+    reversed: bool,
+}
+
+impl<'p> Iterator for Segments<'p> {
+    type Item = &'p str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let next = if self.reversed {
+                self.inner.next_back()
+            } else {
+                self.inner.next()
+            };
+
+            match next {
+                Some("") => continue,
+                next => return next,
+            }
+        }
+    }
+}
+
+/// Resource limits applied by a `Router` to lookup and registration.
+///
+/// Limits are intended for routing untrusted input, to bound the amount of work
+/// done for any single path. Every limit is disabled by default, and each can be
+/// enabled individually:
+///
+/// ```rust
+/// use usher::prelude::*;
+/// use usher::router::{LimitError, Limits};
+///
+/// let mut router: Router<usize> = Router::new(vec![Box::new(StaticParser)]);
+///
+/// router.limits(Limits::new().max_path_bytes(16).max_depth(2));
+/// router.insert("/a/b", 1);
+///
+/// assert_eq!(router.try_insert("/a/b/c", 2), Err(LimitError::Depth(2)));
+/// assert_eq!(router.try_lookup("/a/b/c/d/e/f/g/h/i"), Err(LimitError::PathBytes(16)));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Limits {
+    path_bytes: Option<usize>,
+    segments: Option<usize>,
+    captures: Option<usize>,
+    steps: Option<usize>,
+    depth: Option<usize>,
+}
+
+impl Limits {
+    /// Creates a new set of `Limits`, with every limit disabled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum length of a path in bytes, checked before lookup.
+    pub fn max_path_bytes(mut self, limit: usize) -> Self {
+        self.path_bytes = Some(limit);
+        self
+    }
+
+    /// Sets the maximum number of (non-empty) segments walked during lookup.
+    pub fn max_segments(mut self, limit: usize) -> Self {
+        self.segments = Some(limit);
+        self
+    }
+
+    /// Sets the maximum number of captures found during lookup.
+    pub fn max_captures(mut self, limit: usize) -> Self {
+        self.captures = Some(limit);
+        self
+    }
+
+    /// Sets the maximum number of matcher checks performed during lookup.
+    ///
+    /// Every child checked against a segment counts as a step, so this bounds the
+    /// cost of a lookup through nodes with many children (or expensive matchers).
+    pub fn max_steps(mut self, limit: usize) -> Self {
+        self.steps = Some(limit);
+        self
+    }
+
+    /// Sets the maximum depth of a route (in segments) during registration.
+    pub fn max_depth(mut self, limit: usize) -> Self {
+        self.depth = Some(limit);
+        self
+    }
+}
+
+/// Error type returned when an operation exceeds the `Limits` of a router.
+///
+/// Each variant contains the limit which was exceeded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LimitError {
+    /// The path is longer than the maximum number of bytes.
+    PathBytes(usize),
+    /// The path has more than the maximum number of segments.
+    Segments(usize),
+    /// The path has more than the maximum number of captures.
+    Captures(usize),
+    /// The lookup required more than the maximum number of steps.
+    Steps(usize),
+    /// The route is deeper than the maximum depth.
+    Depth(usize),
+}
+
+impl fmt::Display for LimitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LimitError::PathBytes(limit) => write!(f, "path exceeds {} bytes", limit),
+            LimitError::Segments(limit) => write!(f, "path exceeds {} segments", limit),
+            LimitError::Captures(limit) => write!(f, "path exceeds {} captures", limit),
+            LimitError::Steps(limit) => write!(f, "lookup exceeds {} steps", limit),
+            LimitError::Depth(limit) => write!(f, "route exceeds a depth of {}", limit),
+        }
+    }
+}
+
+impl Error for LimitError {}
+
+/// Policies used to resolve conflicting values when merging routers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Conflict {
+    /// Abort the merge and report the conflicting path.
+    Error,
+    /// Keep the value which already exists in the router.
+    Keep,
+    /// Overwrite the existing value with the incoming value.
+    Overwrite,
+}
+
+/// Error type returned when a mount encounters a conflicting value.
+///
+/// This is also returned when a mounted route would exceed the depth limit of
+/// the router, in which case the exceeded limit is available via `limit`, and
+/// when the mounted router splits paths differently (see `is_incompatible`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MountError {
+    path: String,
+    kind: MountErrorKind,
+}
+
+/// Reasons a mount can fail, as carried by a `MountError`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MountErrorKind {
+    Conflict,
+    Limit(LimitError),
+    Incompatible,
+}
+
+impl MountError {
+    /// Retrieves the path at which the conflict was found.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Retrieves the limit exceeded at the path, if the mount failed on a limit.
+    pub fn limit(&self) -> Option<LimitError> {
+        match self.kind {
+            MountErrorKind::Limit(limit) => Some(limit),
+            _ => None,
+        }
+    }
+
+    /// Determines whether the mount failed due to a different delimiter or direction.
+    pub fn is_incompatible(&self) -> bool {
+        self.kind == MountErrorKind::Incompatible
+    }
+}
+
+impl fmt::Display for MountError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            MountErrorKind::Conflict => write!(f, "conflicting value at path: {}", self.path),
+            MountErrorKind::Limit(limit) => write!(f, "{} at path: {}", limit, self.path),
+            MountErrorKind::Incompatible => {
+                write!(f, "incompatible delimiter at path: {}", self.path)
+            }
+        }
+    }
+}
+
+impl Error for MountError {}
+
+/// Locates the path of the first conflicting value between two nodes.
+///
+/// Children are paired using the same matching as a merge, so this will only
+/// ever report conflicts which would actually occur when merging the nodes.
+fn find_conflict<T, C>(
+    current: &Node<T>,
+    incoming: &Node<T>,
+    path: String,
+    conflicts: &C,
+) -> Option<String>
+where
+    C: Fn(&T, &T) -> bool,
+{
+    let values = current.value().zip(incoming.value());
+
+    if values.is_some_and(|(current, incoming)| conflicts(current, incoming)) {
+        return Some(if path.is_empty() {
+            "/".to_owned()
+        } else {
+            path
+        });
+    }
+
+    incoming.children().iter().find_map(|child| {
+        current
+            .children()
+            .iter()
+            .find(|existing| existing.accepts(child.segment()))
+            .and_then(|existing| {
+                let path = format!("{}/{}", path, child.segment());
+                find_conflict(existing, child, path, conflicts)
+            })
+    })
+}
+
+/// Locates the path of the first value which would exceed a depth limit.
+///
+/// The depth provided is the depth of the node itself within the final tree.
+fn find_too_deep<T>(node: &Node<T>, depth: usize, limit: usize, path: String) -> Option<String> {
+    if depth > limit && node.value().is_some() {
+        return Some(path);
+    }
+
+    node.children().iter().find_map(|child| {
+        let path = format!("{}/{}", path, child.segment());
+        find_too_deep(child, depth + 1, limit, path)
+    })
+}
+
+/// Merges an incoming node into an existing node, using a merge function.
+///
+/// As this function doesn't have any way to report errors, conflicts when
+/// using `Conflict::Error` are expected to be checked prior to the merge.
+fn merge_node<T, M>(current: &mut Node<T>, incoming: Node<T>, merge: &mut M)
+where
+    M: FnMut(&mut T, T),
+{
+    let (value, children) = incoming.into_parts();
+
+    if let Some(value) = value {
+        match current.value_mut() {
+            Some(existing) => merge(existing, value),
+            existing => *existing = Some(value),
+        }
+    }
+
+    for child in children {
+        let existing = current
+            .children_mut()
+            .iter_mut()
+            .find(|existing| existing.accepts(child.segment()));
+
+        match existing {
+            Some(existing) => merge_node(existing, child, merge),
+            None => current.add_child(child),
+        }
     }
 }
 
@@ -1,5 +1,7 @@
 pub mod capture {
+    use std::sync::Arc;
     use usher::capture::*;
+    use usher::prelude::*;
 
     #[test]
     fn finding_captures() {
@@ -18,4 +20,124 @@ pub mod capture {
         let missing = find_capture(path, &captures, "missing");
         assert_eq!(missing, None);
     }
+
+    #[test]
+    fn resolving_params() {
+        let path = "/api/v1/user/123";
+        let captures = vec![("vsn", (5, 7)), ("id", (13, 16))];
+
+        let mut params = Params::from_captures(path, &captures);
+
+        assert_eq!(params.get("id"), Some("123"));
+        assert_eq!(params.get("vsn"), Some("v1"));
+        assert_eq!(params.get("missing"), None);
+
+        params.push("extra", "value");
+
+        assert_eq!(params.len(), 3);
+        assert_eq!(
+            params.iter().collect::<Vec<_>>(),
+            vec![("vsn", "v1"), ("id", "123"), ("extra", "value")]
+        );
+    }
+
+    #[test]
+    fn owning_captures() {
+        let path = "/api/v1/user/123/user/456";
+        let captures = vec![("vsn", (5, 7)), ("id", (13, 16)), ("id", (22, 25))];
+
+        let owned = OwnedCaptures::from(captures.clone());
+
+        assert_eq!(owned.len(), 3);
+        assert_eq!(owned.get("vsn"), Some(5..7));
+        assert_eq!(owned.find(path, "id"), Some("123"));
+        assert_eq!(owned.find(path, "missing"), None);
+
+        assert_eq!(owned.as_captures(), captures);
+        assert_eq!(
+            owned.to_params(path),
+            Params::from_captures(path, &captures)
+        );
+
+        let handle = std::thread::spawn(move || owned.find(path, "vsn").map(str::to_owned));
+
+        assert_eq!(handle.join().unwrap(), Some("v1".to_owned()));
+    }
+
+    #[test]
+    fn interning_captures() {
+        let mut router: Router<usize> =
+            Router::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+
+        router.insert("/users/:id", 1);
+        router.insert("/users/:id/posts/:post", 2);
+
+        let (_, first) = router.lookup_owned("/users/1").unwrap();
+        let cloned = router.clone();
+
+        let (_, second) = router.lookup_owned("/users/2/posts/3").unwrap();
+        let (_, third) = cloned.lookup_owned("/users/4").unwrap();
+
+        let name = |captures: &OwnedCaptures, idx: usize| {
+            captures
+                .iter()
+                .nth(idx)
+                .map(|(name, _)| name.clone())
+                .unwrap()
+        };
+
+        assert_eq!(
+            second.as_captures(),
+            vec![("id", (7, 8)), ("post", (15, 16))]
+        );
+        assert!(Arc::ptr_eq(&name(&first, 0), &name(&second, 0)));
+        assert!(Arc::ptr_eq(&name(&first, 0), &name(&third, 0)));
+        assert_eq!(&*name(&second, 1), "post");
+
+        assert_eq!(router.lookup_owned("/users"), None);
+        assert_eq!(
+            router.lookup_owned("/users/1").map(|(_, c)| c),
+            router
+                .lookup("/users/1")
+                .map(|(_, c)| OwnedCaptures::from(c))
+        );
+    }
+
+    #[test]
+    fn interning_checked_captures() {
+        // a matcher which relies on `is_match` being checked before `capture`
+        struct NumberMatcher;
+
+        impl Matcher for NumberMatcher {
+            fn capture<'a>(&'a self, segment: &str) -> Option<Capture<'a>> {
+                segment.parse::<u64>().unwrap();
+                Some(("id", (0, segment.len())))
+            }
+
+            fn is_match(&self, segment: &str) -> bool {
+                segment.parse::<u64>().is_ok()
+            }
+        }
+
+        let parser = |segment: &str| -> Option<Box<dyn Matcher>> {
+            if segment != "#id" {
+                return None;
+            }
+            Some(Box::new(NumberMatcher))
+        };
+
+        let mut router: Router<usize> = Router::new(vec![Box::new(parser), Box::new(StaticParser)]);
+
+        router.insert("/users/#id", 1);
+
+        let (_, first) = router.lookup_owned("/users/12").unwrap();
+        let (_, second) = router.lookup_owned("/users/34").unwrap();
+
+        assert_eq!(first.find("/users/12", "id"), Some("12"));
+        assert!(Arc::ptr_eq(
+            first.iter().next().unwrap().0,
+            second.iter().next().unwrap().0
+        ));
+        assert_eq!(router.lookup_owned("/users/abc"), None);
+    }
 }
@@ -0,0 +1,589 @@
+pub mod http {
+    use http::{Method, Request};
+    use usher::capture::find_capture;
+    use usher::guard;
+    use usher::http::{request_host, HostRouter, HttpRouter, Lookup, Matched, RequestExt};
+    use usher::normalize::Normalizer;
+    use usher::prelude::*;
+    use usher::router::{Conflict, LimitError, Limits};
+
+    #[test]
+    fn scoped_routing() {
+        let mut router: HttpRouter<String> =
+            HttpRouter::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+
+        router.scope("/api/v1", |s| {
+            s.wrap(|t| format!("v1:{}", t));
+            s.get("/users", "list".to_owned());
+            s.get("/users/:id", "user".to_owned());
+            s.scope("/orders", |s| {
+                s.wrap(|t| format!("orders:{}", t));
+                s.post("/", "create".to_owned());
+            });
+        });
+
+        let n1 = router.handler(&Method::GET, "/api/v1/users");
+        let n2 = router.handler(&Method::GET, "/api/v1/users/1");
+        let n3 = router.handler(&Method::POST, "/api/v1/orders");
+
+        assert_eq!(n1, Some((&"v1:list".to_owned(), vec![])));
+        assert_eq!(n2, Some((&"v1:user".to_owned(), vec![("id", (14, 15))])));
+        assert_eq!(n3, Some((&"v1:orders:create".to_owned(), vec![])));
+    }
+
+    #[test]
+    fn mounted_routing() {
+        let create = || HttpRouter::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+
+        let mut billing: HttpRouter<&str> = create();
+        billing.get("/invoices/:id", "invoice");
+        billing.post("/invoices", "create");
+        billing.guarded(Method::GET, "/status", guard::accept("text/csv"), "csv");
+
+        let mut router: HttpRouter<&str> = create();
+        router.get("/billing/invoices", "list");
+        router.get("/billing/status", "status");
+        router.mount("/billing", billing, Conflict::Error).unwrap();
+
+        let n1 = router.handler(&Method::GET, "/billing/invoices");
+        let n2 = router.handler(&Method::POST, "/billing/invoices");
+        let n3 = router.handler(&Method::GET, "/billing/invoices/1");
+
+        assert_eq!(n1, Some((&"list", vec![])));
+        assert_eq!(n2, Some((&"create", vec![])));
+        assert_eq!(n3, Some((&"invoice", vec![("id", (18, 19))])));
+
+        let request = Request::get("/billing/status")
+            .header("Accept", "text/csv")
+            .body(())
+            .unwrap();
+
+        match router.lookup_request(&request) {
+            Lookup::Found(handler, _) => assert_eq!(*handler, "csv"),
+            _ => panic!("expected guarded handler"),
+        }
+
+        let mut conflicting: HttpRouter<&str> = create();
+        conflicting.get("/invoices", "other");
+        conflicting.delete("/invoices", "clear");
+
+        let error = router
+            .mount("/billing", conflicting.clone(), Conflict::Error)
+            .unwrap_err();
+
+        assert_eq!(error.path(), "/billing/invoices");
+        assert_eq!(router.handler(&Method::DELETE, "/billing/invoices"), None);
+
+        router
+            .mount("/billing", conflicting.clone(), Conflict::Keep)
+            .unwrap();
+
+        let n1 = router.handler(&Method::GET, "/billing/invoices");
+        let n2 = router.handler(&Method::DELETE, "/billing/invoices");
+
+        assert_eq!(n1, Some((&"list", vec![])));
+        assert_eq!(n2, Some((&"clear", vec![])));
+
+        router
+            .mount("/billing", conflicting, Conflict::Overwrite)
+            .unwrap();
+
+        let n1 = router.handler(&Method::GET, "/billing/invoices");
+        assert_eq!(n1, Some((&"other", vec![])));
+    }
+
+    #[test]
+    fn host_routing() {
+        let create = |value: &str| {
+            let mut router: HttpRouter<String> =
+                HttpRouter::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+            router.get("/users/:id", value.to_owned());
+            router
+        };
+
+        let mut router: HostRouter<String> =
+            HostRouter::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+
+        router.host("api.example.com", create("api"));
+        router.host(":tenant.example.com", create("tenant"));
+        router.fallback(create("fallback"));
+
+        let n1 = router.handler(&Method::GET, "api.example.com", "/users/1");
+        let n2 = router.handler(&Method::GET, "acme.example.com:8080", "/users/1");
+        let n3 = router.handler(&Method::GET, "other.org", "/users/1");
+        let n4 = router.handler(&Method::GET, "api.example.com", "/missing");
+
+        assert_eq!(n1, Some((&"api".to_owned(), vec![], vec![("id", (7, 8))])));
+        assert_eq!(
+            n2,
+            Some((
+                &"tenant".to_owned(),
+                vec![("tenant", (0, 4))],
+                vec![("id", (7, 8))]
+            ))
+        );
+        assert_eq!(
+            n3,
+            Some((&"fallback".to_owned(), vec![], vec![("id", (7, 8))]))
+        );
+        assert_eq!(n4, None);
+
+        let mut request = Request::get("/users/9")
+            .header("Host", "a-much-longer-tenant.example.com")
+            .body(())
+            .unwrap();
+
+        let (_, host_captures, path_captures) = router.request_handler(&request).unwrap();
+        let host = request_host(&request).unwrap();
+        let path = request.uri().path();
+
+        assert_eq!(host_captures, vec![("tenant", (0, 20))]);
+        assert_eq!(path_captures, vec![("id", (7, 8))]);
+        assert_eq!(
+            find_capture(host, &host_captures, "tenant"),
+            Some("a-much-longer-tenant")
+        );
+        assert_eq!(find_capture(path, &path_captures, "tenant"), None);
+        assert_eq!(find_capture(path, &path_captures, "id"), Some("9"));
+
+        *request.uri_mut() = "/missing".parse().unwrap();
+
+        assert_eq!(router.route_request(&mut request), Lookup::NotFound);
+        assert_eq!(request.params(), None);
+
+        let mut request = Request::post("/users/1")
+            .header("Host", "acme.example.com")
+            .body(())
+            .unwrap();
+
+        assert_eq!(
+            router.route_request(&mut request),
+            Lookup::MethodNotAllowed(vec![Method::GET])
+        );
+
+        let mut request = Request::get("/users/1")
+            .header("Host", "acme.example.com")
+            .body(())
+            .unwrap();
+
+        assert_eq!(
+            router.route_request(&mut request),
+            Lookup::Found(&"tenant".to_owned(), vec![("id", (7, 8))])
+        );
+        assert_eq!(request.param("tenant"), Some("acme"));
+        assert_eq!(request.param("id"), Some("1"));
+        assert_eq!(request.matched_route(), Some("/users/:id"));
+    }
+
+    #[test]
+    fn fallback_routing() {
+        let mut router: HttpRouter<&str> =
+            HttpRouter::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+
+        router.get("/graphql", "get");
+        router.any("/graphql", "any");
+
+        let n1 = router.resolve(&Method::GET, "/graphql");
+        let n2 = router.resolve(&Method::POST, "/graphql");
+        let n3 = router.resolve(&Method::GET, "/missing");
+
+        assert_eq!(n1, Some((&"get", vec![], Matched::Method)));
+        assert_eq!(n2, Some((&"any", vec![], Matched::Any)));
+        assert_eq!(n3, None);
+
+        router.fallback("fallback");
+
+        let n4 = router.resolve(&Method::GET, "/missing");
+        let n5 = router.handler(&Method::GET, "/missing");
+
+        assert_eq!(n4, Some((&"fallback", vec![], Matched::Fallback)));
+        assert_eq!(n5, Some((&"fallback", vec![])));
+    }
+
+    #[test]
+    fn allowed_routing() {
+        let mut router: HttpRouter<&str> =
+            HttpRouter::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+
+        router.get("/users/:id", "get");
+        router.put("/users/:id", "put");
+        router.fallback("fallback");
+
+        let n1 = router.lookup(&Method::GET, "/users/1");
+        let n2 = router.lookup(&Method::POST, "/users/1");
+        let n3 = router.lookup(&Method::GET, "/users");
+
+        assert_eq!(n1, Lookup::Found(&"get", vec![("id", (7, 8))]));
+        assert_eq!(n2, Lookup::MethodNotAllowed(vec![Method::GET, Method::PUT]));
+        assert_eq!(n3, Lookup::NotFound);
+    }
+
+    #[test]
+    fn automatic_routing() {
+        let mut router: HttpRouter<&str> =
+            HttpRouter::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+
+        router.get("/users", "get");
+        router.post("/users", "post");
+        router.delete("/users/:id", "delete");
+
+        assert_eq!(router.lookup(&Method::HEAD, "/users"), {
+            Lookup::MethodNotAllowed(vec![Method::GET, Method::POST])
+        });
+
+        router.auto_head(true);
+        router.auto_options(true);
+
+        let n1 = router.lookup(&Method::HEAD, "/users");
+        let n2 = router.lookup(&Method::OPTIONS, "/users");
+        let n3 = router.lookup(&Method::OPTIONS, "*");
+        let n4 = router.resolve(&Method::HEAD, "/users");
+
+        assert_eq!(n1, Lookup::Head(&"get", vec![]));
+        assert_eq!(
+            n2,
+            Lookup::Options(vec![
+                Method::GET,
+                Method::HEAD,
+                Method::OPTIONS,
+                Method::POST
+            ])
+        );
+        assert_eq!(
+            n3,
+            Lookup::Options(vec![
+                Method::DELETE,
+                Method::GET,
+                Method::HEAD,
+                Method::OPTIONS,
+                Method::POST
+            ])
+        );
+        assert_eq!(n4, Some((&"get", vec![], Matched::Head)));
+    }
+
+    #[test]
+    fn extension_routing() {
+        let mut router: HttpRouter<&str> =
+            HttpRouter::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+
+        let purge = Method::from_bytes(b"PURGE").unwrap();
+        let mkcol = Method::from_bytes(b"MKCOL").unwrap();
+        let propfind = Method::from_bytes(b"PROPFIND").unwrap();
+
+        router.route(purge.clone(), "/cache/:key", "purge");
+        router.route_many(vec![mkcol.clone(), propfind.clone()], "/dav", "dav");
+
+        let n1 = router.handler(&purge, "/cache/abc");
+        let n2 = router.handler(&propfind, "/dav");
+        let n3 = router.lookup(&Method::GET, "/dav");
+
+        assert_eq!(n1, Some((&"purge", vec![("key", (7, 10))])));
+        assert_eq!(n2, Some((&"dav", vec![])));
+        assert_eq!(n3, Lookup::MethodNotAllowed(vec![mkcol, propfind]));
+    }
+
+    #[test]
+    fn request_routing() {
+        let mut router: HttpRouter<&str> =
+            HttpRouter::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+
+        router.get("/orgs/:org/users/:id", "user");
+
+        let mut request = Request::get("/orgs/acme/users/123").body(()).unwrap();
+        let lookup = router.route_request(&mut request);
+
+        assert_eq!(
+            lookup,
+            Lookup::Found(&"user", vec![("org", (6, 10)), ("id", (17, 20))])
+        );
+        assert_eq!(request.param("org"), Some("acme"));
+        assert_eq!(request.param("id"), Some("123"));
+        assert_eq!(request.param("missing"), None);
+        assert_eq!(request.matched_route(), Some("/orgs/:org/users/:id"));
+
+        let mut request = Request::get("/missing").body(()).unwrap();
+
+        assert_eq!(router.route_request(&mut request), Lookup::NotFound);
+        assert_eq!(request.params(), None);
+        assert_eq!(request.matched_route(), None);
+    }
+
+    #[test]
+    fn normalized_routing() {
+        let mut router: HttpRouter<&str> =
+            HttpRouter::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+
+        router.get("/static/:file", "static");
+        router.get("/admin", "admin");
+
+        let mut request = Request::get("/static/..").body(()).unwrap();
+        let lookup = router.route_request(&mut request);
+
+        assert_eq!(lookup, Lookup::Found(&"static", vec![("file", (8, 10))]));
+        assert_eq!(request.param("file"), Some(".."));
+
+        router.normalize(Normalizer::new());
+
+        let mut request = Request::get("/static/../admin").body(()).unwrap();
+        let lookup = router.route_request(&mut request);
+
+        assert_eq!(lookup, Lookup::Found(&"admin", vec![]));
+        assert_eq!(request.matched_route(), Some("/admin"));
+
+        let mut request = Request::get("/static/css/./../app.js").body(()).unwrap();
+        router.route_request(&mut request);
+
+        assert_eq!(request.param("file"), Some("app.js"));
+
+        let mut request = Request::get("/static/%2e%2e").body(()).unwrap();
+
+        assert_eq!(router.route_request(&mut request), Lookup::InvalidPath);
+        assert_eq!(request.params(), None);
+
+        router.auto_options(true);
+
+        let mut request = Request::options("*").body(()).unwrap();
+
+        assert_eq!(
+            router.route_request(&mut request),
+            Lookup::Options(vec![Method::GET, Method::OPTIONS])
+        );
+
+        router.limits(Limits::new().max_segments(2));
+
+        let n1 = router.lookup(&Method::GET, "/static/app.js");
+        let n2 = router.lookup(&Method::GET, "/static/js/app.js");
+
+        assert_eq!(n1, Lookup::Found(&"static", vec![("file", (8, 14))]));
+        assert_eq!(n2, Lookup::InvalidPath);
+    }
+
+    #[test]
+    fn limited_routing() {
+        let mut router: HttpRouter<&str> =
+            HttpRouter::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+
+        router.limits(Limits::new().max_depth(2).max_path_bytes(8));
+        router.fallback("fallback");
+
+        let depth = Err(LimitError::Depth(2));
+
+        assert_eq!(router.try_route(Method::GET, "/a/b/c", "c"), depth);
+        assert_eq!(router.try_any("/a/b/c", "c"), depth);
+        assert_eq!(
+            router.try_guarded(Method::GET, "/a/b/c", guard::accept("text/csv"), "c"),
+            depth
+        );
+        assert_eq!(
+            router.try_route_many(vec![Method::GET, Method::PUT], "/a/b/c", "c"),
+            depth
+        );
+
+        router.scope("/a", |scope| {
+            assert_eq!(scope.try_route(Method::GET, "/b/c", "c"), depth);
+            assert_eq!(scope.try_route(Method::GET, "/:id", "a"), Ok(()));
+        });
+
+        assert!(router
+            .routes()
+            .iter()
+            .all(|route| route.path().as_str() == "/a/:id"));
+
+        let n1 = router.handler(&Method::GET, "/a/1");
+        let n2 = router.handler(&Method::GET, "/missing");
+        let n3 = router.handler(&Method::GET, "/missing/path");
+
+        assert_eq!(n1, Some((&"a", vec![("id", (3, 4))])));
+        assert_eq!(n2, Some((&"fallback", vec![])));
+        assert_eq!(n3, None);
+    }
+
+    #[test]
+    fn limited_host_routing() {
+        let mut router: HostRouter<&str> =
+            HostRouter::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+
+        let create = |value| {
+            let mut router = HttpRouter::new(vec![Box::new(StaticParser)]);
+            router.get("/", value);
+            router
+        };
+
+        router.limits(Limits::new().max_depth(3).max_segments(3));
+        router.fallback(create("fallback"));
+
+        assert_eq!(
+            router.try_host("a.b.example.com", create("a")),
+            Err(LimitError::Depth(3))
+        );
+        assert_eq!(
+            router.try_host(":tenant.example.com", create("tenant")),
+            Ok(())
+        );
+
+        let n1 = router.handler(&Method::GET, "acme.example.com", "/");
+        let n2 = router.handler(&Method::GET, "example.org", "/");
+        let n3 = router.handler(&Method::GET, "a.acme.example.com", "/");
+
+        assert_eq!(n1, Some((&"tenant", vec![("tenant", (0, 4))], vec![])));
+        assert_eq!(n2, Some((&"fallback", vec![], vec![])));
+        assert_eq!(n3, None);
+
+        let mut request = Request::get("http://a.acme.example.com/").body(()).unwrap();
+
+        assert_eq!(router.route_request(&mut request), Lookup::InvalidPath);
+    }
+
+    #[test]
+    fn guarded_allowed_routing() {
+        let mut router: HttpRouter<&str> =
+            HttpRouter::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+
+        router.guarded(Method::GET, "/export", guard::accept("text/csv"), "csv");
+        router.post("/export", "create");
+
+        // guarded handlers are never available without a request
+        assert_eq!(
+            router.lookup(&Method::GET, "/export"),
+            Lookup::MethodNotAllowed(vec![Method::POST])
+        );
+
+        let request = Request::get("/export").body(()).unwrap();
+        assert_eq!(
+            router.lookup_request(&request),
+            Lookup::MethodNotAllowed(vec![Method::POST])
+        );
+
+        let request = Request::delete("/export")
+            .header("Accept", "text/csv")
+            .body(())
+            .unwrap();
+        assert_eq!(
+            router.lookup_request(&request),
+            Lookup::MethodNotAllowed(vec![Method::GET, Method::POST])
+        );
+
+        router.auto_head(true);
+        router.auto_options(true);
+
+        assert_eq!(
+            router.lookup(&Method::OPTIONS, "/export"),
+            Lookup::Options(vec![Method::OPTIONS, Method::POST])
+        );
+
+        let request = Request::options("/export")
+            .header("Accept", "text/csv")
+            .body(())
+            .unwrap();
+        assert_eq!(
+            router.lookup_request(&request),
+            Lookup::Options(vec![
+                Method::GET,
+                Method::HEAD,
+                Method::OPTIONS,
+                Method::POST
+            ])
+        );
+    }
+
+    #[test]
+    fn guarded_routing() {
+        let mut router: HttpRouter<&str> =
+            HttpRouter::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+
+        router.guarded(Method::GET, "/users", guard::accept("text/csv"), "csv");
+        router.guarded(
+            Method::GET,
+            "/users",
+            guard::all(vec![
+                guard::header("X-Api-Version", "2"),
+                guard::query("format", "full"),
+            ]),
+            "v2",
+        );
+        router.get("/users", "list");
+        router.guarded(
+            Method::POST,
+            "/users",
+            guard::content_type("application/json"),
+            "create",
+        );
+
+        let route = |request: Request<()>| match router.lookup_request(&request) {
+            Lookup::Found(handler, _) => Some(*handler),
+            _ => None,
+        };
+
+        let request = Request::get("/users")
+            .header("Accept", "application/json, text/csv;q=0.9")
+            .body(())
+            .unwrap();
+        assert_eq!(route(request), Some("csv"));
+
+        let request = Request::get("/users?format=full")
+            .header("X-Api-Version", "2")
+            .body(())
+            .unwrap();
+        assert_eq!(route(request), Some("v2"));
+
+        let request = Request::get("/users").header("X-Api-Version", "2").body(());
+        assert_eq!(route(request.unwrap()), Some("list"));
+
+        let request = Request::post("/users")
+            .header("Content-Type", "application/json; charset=utf-8")
+            .body(())
+            .unwrap();
+        assert_eq!(route(request), Some("create"));
+
+        let request = Request::post("/users").body(()).unwrap();
+        assert_eq!(route(request), None);
+
+        // guards are skipped when routing without a request
+        assert_eq!(
+            router.handler(&Method::GET, "/users"),
+            Some((&"list", vec![]))
+        );
+
+        let pattern = RoutePattern::parse("/users").unwrap();
+        assert_eq!(router.routes()[0].path(), &pattern);
+
+        let routes: Vec<_> = router
+            .routes()
+            .into_iter()
+            .map(|route| {
+                (
+                    route.path().to_string(),
+                    route.method().map(Method::to_string),
+                    route.guard().map(|guard| guard.describe()),
+                    *route.handler(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            routes,
+            vec![
+                (
+                    "/users".to_owned(),
+                    Some("GET".to_owned()),
+                    Some("accept(text/csv)".to_owned()),
+                    "csv"
+                ),
+                (
+                    "/users".to_owned(),
+                    Some("GET".to_owned()),
+                    Some("header(x-api-version: 2) && query(format=full)".to_owned()),
+                    "v2"
+                ),
+                ("/users".to_owned(), Some("GET".to_owned()), None, "list"),
+                (
+                    "/users".to_owned(),
+                    Some("POST".to_owned()),
+                    Some("content_type(application/json)".to_owned()),
+                    "create"
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,128 @@
+pub mod hyper {
+    use hyper::service::Service;
+    use hyper::{body, Body, Request, Response, StatusCode};
+    use usher::capture::Params;
+    use usher::http::HttpRouter;
+    use usher::hyper::{handler, layer, middleware, Error, Handler, Next};
+    use usher::normalize::Normalizer;
+    use usher::prelude::*;
+
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test]
+    async fn service_routing() {
+        let mut router: HttpRouter<Box<dyn Handler>> =
+            HttpRouter::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+
+        router.get(
+            "/hello/:name",
+            handler(|_req, params| async move {
+                let body = format!("Hello, {}!", params.get("name").unwrap());
+                Ok::<_, Error>(Response::new(body.into()))
+            }),
+        );
+
+        router.get(
+            "/error",
+            handler(|_req, _params| async move { Err::<Response<Body>, Error>("broken".into()) }),
+        );
+
+        let mut make = router.into_make_service().on_error(|err: Error| {
+            let mut response = Response::new(err.to_string().into());
+            *response.status_mut() = StatusCode::BAD_GATEWAY;
+            response
+        });
+
+        let mut service = make.call(()).await.unwrap();
+
+        let req = Request::get("/hello/usher").body(Body::empty()).unwrap();
+        let res = service.call(req).await.unwrap();
+        let body = body::to_bytes(res.into_body()).await.unwrap();
+        assert_eq!(body, "Hello, usher!");
+
+        let req = Request::get("/error").body(Body::empty()).unwrap();
+        let res = service.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_GATEWAY);
+        let body = body::to_bytes(res.into_body()).await.unwrap();
+        assert_eq!(body, "broken");
+
+        let req = Request::get("/missing").body(Body::empty()).unwrap();
+        let res = service.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn normalized_service_routing() {
+        let mut router: HttpRouter<Box<dyn Handler>> =
+            HttpRouter::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+
+        router.get(
+            "/static/:dir/:file",
+            handler(|_req, params| async move {
+                let body = format!(
+                    "{}/{}",
+                    params.get("dir").unwrap(),
+                    params.get("file").unwrap()
+                );
+                Ok::<_, Error>(Response::new(body.into()))
+            }),
+        );
+
+        router.normalize(Normalizer::new());
+
+        let mut service = router.into_make_service().call(()).await.unwrap();
+
+        let req = Request::get("/static/css/a.css")
+            .body(Body::empty())
+            .unwrap();
+        let res = service.call(req).await.unwrap();
+        let body = body::to_bytes(res.into_body()).await.unwrap();
+        assert_eq!(body, "css/a.css");
+
+        let req = Request::get("/static/../admin")
+            .body(Body::empty())
+            .unwrap();
+        let res = service.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn middleware_routing() {
+        let mut router: HttpRouter<Box<dyn Handler>> =
+            HttpRouter::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let record = |name: &'static str| {
+            let log = log.clone();
+            move |req: Request<Body>, params: Params, next: Next| {
+                let id = params.get("id").unwrap_or("-").to_owned();
+                log.lock().unwrap().push(format!("{}:{}", name, id));
+                next.run(req, params)
+            }
+        };
+
+        router.scope("/admin", |s| {
+            s.wrap(middleware(record("outer")));
+            s.scope("/users", |s| {
+                s.wrap(middleware(record("inner")));
+                s.get(
+                    "/:id",
+                    layer(
+                        record("route"),
+                        handler(|_req, _params| async move {
+                            Ok::<_, Error>(Response::new(Body::empty()))
+                        }),
+                    ),
+                );
+            });
+        });
+
+        let mut service = router.into_make_service().call(()).await.unwrap();
+
+        let req = Request::get("/admin/users/1").body(Body::empty()).unwrap();
+        let res = service.call(req).await.unwrap();
+
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(*log.lock().unwrap(), vec!["outer:1", "inner:1", "route:1"]);
+    }
+}
@@ -0,0 +1,156 @@
+pub mod macros {
+    use http::Method;
+    use usher::http::Lookup;
+
+    #[test]
+    fn macro_routing() {
+        let router = usher::router! {
+            "/" => 0,
+            "/users/:id" => 1,
+            "/users/:id/posts/" => 2,
+            "//about" => 3,
+        };
+
+        assert_eq!(router.lookup("/"), Some((&0, vec![])));
+        assert_eq!(
+            router.lookup("/users/123"),
+            Some((&1, vec![("id", (7, 10))]))
+        );
+        assert_eq!(
+            router.lookup("/users/123/posts"),
+            Some((&2, vec![("id", (7, 10))]))
+        );
+        assert_eq!(router.lookup("/about"), Some((&3, vec![])));
+    }
+
+    #[test]
+    fn macro_pattern_routing() {
+        let router = usher::router! {
+            "/users/\\:me" => 0,
+            "/users/:id(\\d+)" => 1,
+            "/users/:name" => 2,
+            "/files/*path" => 3,
+        };
+
+        assert_eq!(router.lookup("/users/:me"), Some((&0, vec![])));
+        assert_eq!(router.lookup("/users/1"), Some((&1, vec![("id", (7, 8))])));
+        assert_eq!(
+            router.lookup("/users/me"),
+            Some((&2, vec![("name", (7, 9))]))
+        );
+        assert_eq!(
+            router.lookup("/files/a/b"),
+            Some((&3, vec![("path", (7, 10))]))
+        );
+    }
+
+    #[test]
+    fn macro_hygiene() {
+        let parsers = 7u8;
+        let router = 8u8;
+
+        let router = usher::router! {
+            "/parsers" => parsers,
+            "/router" => router,
+        };
+
+        assert_eq!(router.lookup("/parsers"), Some((&7, vec![])));
+        assert_eq!(router.lookup("/router"), Some((&8, vec![])));
+    }
+
+    #[test]
+    fn macro_http_routing() {
+        let router = usher::router! {
+            GET "/users" => "list",
+            POST "/users" => "create",
+            GET "/users/:id" => "get",
+            ANY "/users/:id" => "any",
+            PROPFIND "/files/:name" => "propfind",
+        };
+
+        assert_eq!(
+            router.lookup(&Method::GET, "/users"),
+            Lookup::Found(&"list", vec![])
+        );
+        assert_eq!(
+            router.lookup(&Method::POST, "/users"),
+            Lookup::Found(&"create", vec![])
+        );
+        assert_eq!(
+            router.lookup(&Method::DELETE, "/users/1"),
+            Lookup::Found(&"any", vec![("id", (7, 8))])
+        );
+
+        let propfind = Method::from_bytes(b"PROPFIND").unwrap();
+        assert_eq!(
+            router.lookup(&propfind, "/files/a"),
+            Lookup::Found(&"propfind", vec![("name", (7, 8))])
+        );
+    }
+
+    #[derive(Debug, PartialEq, usher::Route)]
+    enum Route {
+        #[route("/")]
+        Home,
+        #[route("/users/:id")]
+        User { id: u64 },
+        #[route("/users/:id/posts/:slug")]
+        Post { id: u64, slug: String },
+        #[route("/files/:kind(raw|text)/*path")]
+        File { kind: String, path: String },
+    }
+
+    #[test]
+    fn derived_routing() {
+        assert_eq!(Route::from_path("/"), Some(Route::Home));
+        assert_eq!(
+            Route::from_path("/users/123"),
+            Some(Route::User { id: 123 })
+        );
+        assert_eq!(
+            Route::from_path("/users/123/posts/hello"),
+            Some(Route::Post {
+                id: 123,
+                slug: "hello".to_owned()
+            })
+        );
+        assert_eq!(Route::from_path("/users/abc"), None);
+        assert_eq!(Route::from_path("/missing"), None);
+
+        let routes = vec![
+            Route::Home,
+            Route::User { id: 123 },
+            Route::Post {
+                id: 123,
+                slug: "hello".to_owned(),
+            },
+        ];
+
+        for route in routes {
+            assert_eq!(Route::from_path(&route.to_string()), Some(route));
+        }
+
+        let route = Route::Post {
+            id: 1,
+            slug: "a/b c%".to_owned(),
+        };
+
+        assert_eq!(route.to_string(), "/users/1/posts/a%2Fb%20c%25");
+        assert_eq!(Route::from_path(&route.to_string()), Some(route));
+        assert_eq!(Route::from_path("/users/1/posts/a%2"), None);
+
+        assert_eq!(
+            Route::router().lookup("/users/1").map(|(_, c)| c),
+            Some(vec![("id", (7, 8))])
+        );
+
+        let route = Route::File {
+            kind: "raw".to_owned(),
+            path: "a/b c.txt".to_owned(),
+        };
+
+        assert_eq!(route.to_string(), "/files/raw/a/b%20c.txt");
+        assert_eq!(Route::from_path(&route.to_string()), Some(route));
+        assert_eq!(Route::from_path("/files/zip/a.txt"), None);
+    }
+}
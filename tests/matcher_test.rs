@@ -1,5 +1,6 @@
 pub mod matcher {
     use usher::matcher::*;
+    use usher::prelude::*;
 
     #[test]
     fn static_matching() {
@@ -33,4 +34,35 @@ pub mod matcher {
         assert_eq!(matcher.capture("value"), None);
         assert_eq!(matcher.capture("not-value"), None);
     }
+
+    #[test]
+    fn cloning_matchers() {
+        // a matcher which is deliberately not `Clone`
+        struct PrefixMatcher(String);
+
+        impl Matcher for PrefixMatcher {
+            fn is_match(&self, segment: &str) -> bool {
+                segment.starts_with(&self.0)
+            }
+        }
+
+        struct PrefixParser;
+
+        impl Parser for PrefixParser {
+            fn parse(&self, segment: &str) -> Option<Box<dyn Matcher>> {
+                let prefix = segment.strip_suffix('*')?;
+                Some(Box::new(PrefixMatcher(prefix.to_owned())))
+            }
+        }
+
+        let mut router: Router<usize> =
+            Router::new(vec![Box::new(PrefixParser), Box::new(StaticParser)]);
+
+        router.insert("/files/img*", 1);
+
+        let cloned = router.clone();
+
+        assert_eq!(cloned.lookup("/files/img-1.png"), Some((&1, vec![])));
+        assert_eq!(cloned.lookup("/files/doc.pdf"), None);
+    }
 }
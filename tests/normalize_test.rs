@@ -0,0 +1,119 @@
+pub mod normalize {
+    use std::borrow::Cow;
+    use usher::normalize::{self, EncodedPolicy, NormalizeError, Normalizer};
+    use usher::prelude::*;
+
+    #[test]
+    fn normalizing_paths() {
+        let normalizer = Normalizer::new();
+
+        assert_eq!(normalizer.normalize("/a/b/c"), Ok(Cow::Borrowed("/a/b/c")));
+        assert_eq!(normalizer.normalize("/a/./b/../c"), Ok("/a/c".into()));
+        assert_eq!(normalizer.normalize("//a//b/"), Ok("/a/b".into()));
+        assert_eq!(normalizer.normalize("/../../etc"), Ok("/etc".into()));
+        assert_eq!(normalizer.normalize("a/.."), Ok("/".into()));
+        assert_eq!(normalizer.normalize("/a/..."), Ok("/a/...".into()));
+        assert_eq!(normalizer.normalize("/a%20b/%41"), Ok("/a%20b/%41".into()));
+
+        assert!(matches!(normalizer.normalize("/a/b"), Ok(Cow::Borrowed(_))));
+        assert!(matches!(normalizer.normalize("/a/"), Ok(Cow::Owned(_))));
+    }
+
+    #[test]
+    fn handling_encoded_paths() {
+        let rejecting = Normalizer::new();
+
+        assert_eq!(
+            rejecting.normalize("/static/%2e%2E/admin"),
+            Err(NormalizeError::EncodedDot)
+        );
+        assert_eq!(
+            rejecting.normalize("/static/..%2Fadmin"),
+            Err(NormalizeError::EncodedSlash)
+        );
+        assert_eq!(
+            rejecting.normalize("/static/..%5cadmin"),
+            Err(NormalizeError::EncodedSlash)
+        );
+        assert_eq!(rejecting.normalize("/a/%2"), Ok("/a/%2".into()));
+
+        let decoding = Normalizer::new().encoded(EncodedPolicy::Decode);
+
+        assert_eq!(
+            decoding.normalize("/static/%2e%2E/admin"),
+            Ok("/admin".into())
+        );
+        assert_eq!(
+            decoding.normalize("/static/a%2F..%2fb"),
+            Ok("/static/b".into())
+        );
+        assert_eq!(
+            decoding.normalize("/static/..%5Cadmin"),
+            Ok("/admin".into())
+        );
+        assert_eq!(decoding.normalize("/%252e%252e"), Ok("/%252e%252e".into()));
+    }
+
+    #[test]
+    fn normalized_captures() {
+        let mut router: Router<usize> =
+            Router::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+
+        router.insert("/static/:file", 0);
+        router.insert("/admin", 1);
+
+        let normalizer = Normalizer::new().encoded(EncodedPolicy::Decode);
+        let paths = [
+            "/static/../admin",
+            "/static/x/../../admin",
+            "/static/%2E%2e/admin",
+        ];
+
+        for path in &paths {
+            let normalized = normalizer.normalize(path).unwrap();
+            let (value, captures) = router.lookup(&normalized).unwrap();
+
+            assert_eq!(value, &1);
+            assert!(captures.is_empty());
+        }
+
+        let normalized = normalizer.normalize("/static/./css/../app.js").unwrap();
+        let (value, captures) = router.lookup(&normalized).unwrap();
+        let (start, end) = captures[0].1;
+
+        assert_eq!(value, &0);
+        assert_eq!(&normalized[start..end], "app.js");
+    }
+
+    #[test]
+    #[cfg(feature = "unicode-normalization")]
+    fn normalizing_unicode() {
+        let normalizer = Normalizer::new().nfc(true);
+
+        assert_eq!(
+            normalizer.normalize("/cafe\u{301}"),
+            Ok("/caf\u{e9}".into())
+        );
+        assert_eq!(
+            normalizer.normalize("/caf\u{e9}"),
+            Ok(Cow::Borrowed("/caf\u{e9}"))
+        );
+        assert_eq!(
+            Normalizer::new().normalize("/cafe\u{301}"),
+            Ok(Cow::Borrowed("/cafe\u{301}"))
+        );
+    }
+
+    #[test]
+    fn encoding_segments() {
+        assert_eq!(normalize::encode_segment("a-b"), Cow::Borrowed("a-b"));
+        assert_eq!(normalize::encode_segment("a/b%"), "a%2Fb%25");
+        assert_eq!(normalize::encode_segment("é"), "%C3%A9");
+
+        assert_eq!(normalize::decode_segment("a-b"), Some(Cow::Borrowed("a-b")));
+        assert_eq!(normalize::decode_segment("a%2fb%25").unwrap(), "a/b%");
+        assert_eq!(normalize::decode_segment("%C3%A9").unwrap(), "é");
+        assert_eq!(normalize::decode_segment("a%2"), None);
+        assert_eq!(normalize::decode_segment("%FF"), None);
+    }
+}
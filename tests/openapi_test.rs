@@ -0,0 +1,241 @@
+pub mod openapi {
+    use http::Method;
+    use serde_json::json;
+    use usher::capture::Capture;
+    use usher::guard;
+    use usher::http::HttpRouter;
+    use usher::openapi::{self, LoadError, Operation};
+    use usher::prelude::*;
+
+    // a matcher for numeric identifiers, described as integers
+    struct IdMatcher;
+
+    impl Matcher for IdMatcher {
+        fn capture<'a>(&'a self, segment: &str) -> Option<Capture<'a>> {
+            Some(("id", (0, segment.len())))
+        }
+
+        fn is_match(&self, segment: &str) -> bool {
+            segment.bytes().all(|b| b.is_ascii_digit())
+        }
+
+        fn schema_type(&self) -> Option<&str> {
+            Some("integer")
+        }
+    }
+
+    struct IdParser;
+
+    impl Parser for IdParser {
+        fn parse(&self, segment: &str) -> Option<Box<dyn Matcher>> {
+            if segment != ":id" {
+                return None;
+            }
+            Some(Box::new(IdMatcher))
+        }
+    }
+
+    #[test]
+    fn generating_paths() {
+        let mut router: HttpRouter<&str> = HttpRouter::new(vec![
+            Box::new(IdParser),
+            Box::new(DynamicParser),
+            Box::new(StaticParser),
+        ]);
+
+        router.get("/orgs/:org/users", "listUsers");
+        router.post("/orgs/:org/users", "createUser");
+        router.guarded(
+            Method::GET,
+            "/orgs/:org/users/:id",
+            guard::accept("text/csv"),
+            "exportUser",
+        );
+        router.get("/orgs/:org/users/:id", "getUser");
+        router.delete("/orgs/:org/users/:id", "deleteUser");
+        router.get("/files/*path", "getFile");
+        router.get("/tags/:tag([a-z]+)", "getTag");
+        router.any("/health", "health");
+
+        let paths = router.openapi_paths(|handler| {
+            let operation = Operation::new().tag("users").operation_id(*handler);
+            match *handler {
+                "getUser" => operation.summary("Retrieve a user"),
+                "deleteUser" => operation.parameter("id", json!({ "type": "string" })),
+                _ => operation,
+            }
+        });
+
+        let org = json!({
+            "name": "org",
+            "in": "path",
+            "required": true,
+            "schema": { "type": "string" }
+        });
+
+        let responses = json!({ "default": { "description": "Default response" } });
+
+        assert_eq!(
+            paths,
+            json!({
+                "/orgs/{org}/users": {
+                    "get": {
+                        "operationId": "listUsers",
+                        "tags": ["users"],
+                        "parameters": [org],
+                        "responses": responses
+                    },
+                    "post": {
+                        "operationId": "createUser",
+                        "tags": ["users"],
+                        "parameters": [org],
+                        "responses": responses
+                    }
+                },
+                "/orgs/{org}/users/{id}": {
+                    "delete": {
+                        "operationId": "deleteUser",
+                        "tags": ["users"],
+                        "parameters": [org, {
+                            "name": "id",
+                            "in": "path",
+                            "required": true,
+                            "schema": { "type": "string" }
+                        }],
+                        "responses": responses
+                    },
+                    "get": {
+                        "summary": "Retrieve a user",
+                        "operationId": "getUser",
+                        "tags": ["users"],
+                        "parameters": [org, {
+                            "name": "id",
+                            "in": "path",
+                            "required": true,
+                            "schema": { "type": "integer" }
+                        }],
+                        "responses": responses
+                    }
+                },
+                "/files/{path}": {
+                    "get": {
+                        "operationId": "getFile",
+                        "tags": ["users"],
+                        "parameters": [{
+                            "name": "path",
+                            "in": "path",
+                            "required": true,
+                            "schema": { "type": "string" }
+                        }],
+                        "responses": responses
+                    }
+                },
+                "/tags/{tag}": {
+                    "get": {
+                        "operationId": "getTag",
+                        "tags": ["users"],
+                        "parameters": [{
+                            "name": "tag",
+                            "in": "path",
+                            "required": true,
+                            "schema": { "type": "string", "pattern": "^(?:[a-z]+)$" }
+                        }],
+                        "responses": responses
+                    }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn loading_paths() {
+        let document = r#"{
+            "openapi": "3.0.0",
+            "paths": {
+                "/orgs/{org}/users": {
+                    "parameters": [],
+                    "get": { "operationId": "listUsers" },
+                    "post": { "operationId": "createUser" }
+                },
+                "/orgs/{org}/users/{id}": {
+                    "get": { "operationId": "getUser" }
+                }
+            }
+        }"#;
+
+        let parsers =
+            || -> Vec<Box<dyn Parser>> { vec![Box::new(DynamicParser), Box::new(StaticParser)] };
+        let router = openapi::from_json(document, parsers()).unwrap();
+
+        let (id, captures) = router
+            .handler(&Method::GET, "/orgs/acme/users/123")
+            .unwrap();
+
+        assert_eq!(id.as_str(), "getUser");
+        assert_eq!(captures, vec![("org", (6, 10)), ("id", (17, 20))]);
+
+        let (id, _) = router.handler(&Method::POST, "/orgs/acme/users").unwrap();
+        assert_eq!(id.as_str(), "createUser");
+
+        let error = router
+            .clone()
+            .bind(|id| Some(id.to_string()).filter(|id| id != "getUser"))
+            .err()
+            .unwrap();
+
+        assert_eq!(error.missing().len(), 1);
+        assert_eq!(
+            error.to_string(),
+            "no handlers bound for operations: getUser"
+        );
+
+        let bound = router.bind(|id| Some(id.as_str().len())).unwrap();
+        let (len, _) = bound.handler(&Method::GET, "/orgs/acme/users").unwrap();
+        assert_eq!(*len, 9);
+
+        let document = r#"{ "paths": { "/files/{name}.json": {} } }"#;
+        let error = openapi::from_json(document, parsers()).err().unwrap();
+        assert_eq!(
+            error,
+            LoadError::UnsupportedPath("/files/{name}.json".to_owned())
+        );
+
+        let document = r#"{ "paths": { "/files": { "get": {} } } }"#;
+        let error = openapi::from_json(document, parsers()).err().unwrap();
+        assert_eq!(error.to_string(), "missing operationId for GET /files");
+
+        let document = r#"{
+            "paths": {
+                "/files/{id}": { "get": { "operationId": "getFile" } },
+                "/files/{name}": {
+                    "get": { "operationId": "findFile" },
+                    "put": { "operationId": "putFile" }
+                }
+            }
+        }"#;
+
+        let error = openapi::from_json(document, parsers()).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "operations getFile and findFile both route to GET /files/{name}"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "openapi-yaml")]
+    fn loading_yaml_paths() {
+        let document = "
+openapi: 3.0.0
+paths:
+  /users/{id}:
+    delete:
+      operationId: deleteUser
+";
+
+        let parsers: Vec<Box<dyn Parser>> = vec![Box::new(DynamicParser), Box::new(StaticParser)];
+        let router = openapi::from_yaml(document, parsers).unwrap();
+        let (id, _) = router.handler(&Method::DELETE, "/users/123").unwrap();
+
+        assert_eq!(id.as_str(), "deleteUser");
+    }
+}
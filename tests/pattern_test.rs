@@ -0,0 +1,233 @@
+pub mod pattern {
+    use usher::capture::Params;
+    use usher::pattern::{PatternError, Segment};
+    use usher::prelude::*;
+    use usher::router::{LimitError, Limits};
+
+    #[test]
+    fn parsing_patterns() {
+        let pattern = RoutePattern::parse("//users/:id(\\d+)//\\:me/a\\b/*rest/").unwrap();
+
+        assert_eq!(
+            pattern.segments(),
+            &[
+                Segment::Literal("users".to_owned()),
+                Segment::Constrained {
+                    name: "id".to_owned(),
+                    constraint: "\\d+".to_owned(),
+                },
+                Segment::Literal(":me".to_owned()),
+                Segment::Literal("a\\b".to_owned()),
+                Segment::Wildcard("rest".to_owned()),
+            ]
+        );
+
+        assert_eq!(pattern.to_string(), "/users/:id(\\d+)/\\:me/a\\b/*rest");
+        assert_eq!(pattern.params().collect::<Vec<_>>(), vec!["id", "rest"]);
+        assert_eq!(pattern.to_string().parse::<RoutePattern>(), Ok(pattern));
+
+        let escaped = RoutePattern::parse("/\\*/\\\\:x/:name").unwrap();
+
+        assert_eq!(
+            escaped.segments(),
+            &[
+                Segment::Literal("*".to_owned()),
+                Segment::Literal("\\:x".to_owned()),
+                Segment::Parameter("name".to_owned()),
+            ]
+        );
+        assert_eq!(escaped.to_string(), "/\\*/\\\\:x/:name");
+
+        assert_eq!(RoutePattern::parse("/").unwrap().to_string(), "/");
+
+        let errors = vec![
+            ("users", PatternError::MissingSlash),
+            ("/users/:", PatternError::MissingName),
+            ("/files/*", PatternError::MissingName),
+            ("/:id/:id", PatternError::DuplicateName("id".to_owned())),
+            ("/:id/*id", PatternError::DuplicateName("id".to_owned())),
+            (
+                "/:id()",
+                PatternError::InvalidConstraint(":id()".to_owned()),
+            ),
+            (
+                "/:id(\\d+",
+                PatternError::InvalidConstraint(":id(\\d+".to_owned()),
+            ),
+            (
+                "/:id([)",
+                PatternError::InvalidConstraint(":id([)".to_owned()),
+            ),
+            (
+                "/files/*path/raw",
+                PatternError::MisplacedWildcard("path".to_owned()),
+            ),
+        ];
+
+        for (pattern, error) in errors {
+            assert_eq!(RoutePattern::parse(pattern), Err(error));
+        }
+    }
+
+    #[test]
+    fn reversing_patterns() {
+        let pattern = RoutePattern::parse("/users/:id/files/:name").unwrap();
+
+        let mut params = Params::new();
+        params.push("id", "123");
+
+        assert_eq!(pattern.reverse(&params), None);
+
+        params.push("name", "b.txt");
+
+        assert_eq!(
+            pattern.reverse(&params),
+            Some("/users/123/files/b.txt".to_owned())
+        );
+
+        let mut params = Params::new();
+        params.push("id", "1/2");
+        params.push("name", "a");
+
+        assert_eq!(pattern.reverse(&params), None);
+        assert_eq!(
+            RoutePattern::parse("/").unwrap().reverse(&Params::new()),
+            Some("/".to_owned())
+        );
+
+        let pattern = RoutePattern::parse("/\\:users/:id(\\d+)/*path").unwrap();
+
+        let mut params = Params::new();
+        params.push("id", "12");
+        params.push("path", "a/b.txt");
+
+        assert_eq!(
+            pattern.reverse(&params),
+            Some("/:users/12/a/b.txt".to_owned())
+        );
+
+        let mut params = Params::new();
+        params.push("id", "abc");
+        params.push("path", "a/b.txt");
+
+        assert_eq!(pattern.reverse(&params), None);
+
+        let mut params = Params::new();
+        params.push("id", "12");
+        params.push("path", "");
+
+        assert_eq!(pattern.reverse(&params), None);
+    }
+
+    #[test]
+    fn pattern_routing() {
+        let mut router: Router<usize> =
+            Router::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+
+        let literal = RoutePattern::parse("/tags/\\:all").unwrap();
+        let dynamic = RoutePattern::parse("/tags/:tag").unwrap();
+
+        router.insert(&literal, 1);
+        router.insert(&literal, 2);
+        router.insert(&dynamic, 3);
+
+        assert_eq!(router.routes().len(), 2);
+        assert_eq!(router.lookup("/tags/:all"), Some((&2, vec![])));
+        assert_eq!(
+            router.lookup("/tags/rust"),
+            Some((&3, vec![("tag", (6, 10))]))
+        );
+        assert_eq!(router.matched_route("/tags/:all"), Some(literal.clone()));
+        assert_eq!(router.matched_route("/tags/rust"), Some(dynamic.clone()));
+
+        let routes: Vec<_> = router.routes().into_iter().map(|(p, _)| p).collect();
+        assert_eq!(routes, vec![literal, dynamic]);
+    }
+
+    #[test]
+    fn constrained_routing() {
+        let mut router: Router<usize> =
+            Router::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+
+        router.insert("/users/42", 1);
+        router.insert("/users/:id(\\d+)", 2);
+        router.insert("/users/:name", 3);
+
+        assert_eq!(router.lookup("/users/42"), Some((&1, vec![])));
+        assert_eq!(router.lookup("/users/12"), Some((&2, vec![("id", (7, 9))])));
+        assert_eq!(
+            router.lookup("/users/abc"),
+            Some((&3, vec![("name", (7, 10))]))
+        );
+        assert_eq!(
+            router.lookup("/users/1a"),
+            Some((&3, vec![("name", (7, 9))]))
+        );
+        assert_eq!(router.routes().len(), 3);
+
+        assert_eq!(
+            router.matched_route("/users/12").unwrap().to_string(),
+            "/users/:id(\\d+)"
+        );
+    }
+
+    #[test]
+    fn wildcard_routing() {
+        let mut router: Router<usize> =
+            Router::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+
+        router.insert("/files/readme", 1);
+        router.insert("/files/*path", 2);
+        router.insert("/files/*path/raw", 3);
+
+        assert_eq!(router.lookup("/files/readme"), Some((&1, vec![])));
+        assert_eq!(
+            router.lookup("/files/a"),
+            Some((&2, vec![("path", (7, 8))]))
+        );
+        assert_eq!(
+            router.lookup("/files/a//b/raw/"),
+            Some((&2, vec![("path", (7, 15))]))
+        );
+        assert_eq!(router.lookup("/files"), None);
+
+        let (_, captures, offset) = router.lookup_prefix("/files/a/b").unwrap();
+        assert_eq!((captures, offset), (vec![("path", (7, 10))], 10));
+
+        router.limits(Limits::new().max_segments(3));
+
+        assert!(router.try_lookup("/files/a/b").is_ok());
+        assert_eq!(
+            router.try_lookup("/files/a/b/c"),
+            Err(LimitError::Segments(3))
+        );
+
+        let mut hosts: Router<usize> = Router::with_delimiter(
+            vec![Box::new(DynamicParser), Box::new(StaticParser)],
+            '.',
+            true,
+        );
+
+        hosts.insert("*sub.example.com", 1);
+
+        assert_eq!(
+            hosts.lookup("a.b.example.com"),
+            Some((&1, vec![("sub", (0, 3))]))
+        );
+        assert_eq!(
+            hosts.matched_route("a.example.com").unwrap().as_str(),
+            "*sub.example.com"
+        );
+    }
+
+    #[test]
+    fn escaped_literals() {
+        let mut router: Router<usize> = Router::new(vec![Box::new(StaticParser)]);
+
+        // only a leading escape of `:`, `*` or `\` is removed from a literal
+        router.insert("/\\:id/a\\b/\\c", 1);
+
+        assert_eq!(router.lookup("/:id/a\\b/\\c"), Some((&1, vec![])));
+        assert_eq!(router.lookup("/\\:id/a\\b/\\c"), None);
+    }
+}
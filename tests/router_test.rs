@@ -1,5 +1,6 @@
 pub mod router {
     use usher::prelude::*;
+    use usher::router::{Conflict, LimitError, Limits};
 
     #[test]
     fn basic_routing() {
@@ -57,4 +58,258 @@ pub mod router {
         assert_eq!(n2, None);
         assert_eq!(n3, None);
     }
+
+    #[test]
+    fn mounted_routing() {
+        let mut billing: Router<usize> =
+            Router::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+
+        billing.insert("/", 1);
+        billing.insert("/invoices/:id", 2);
+
+        let mut router: Router<usize> =
+            Router::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+
+        router.insert("/billing/status", 3);
+        router.mount("/billing", billing, Conflict::Error).unwrap();
+
+        let n1 = router.lookup("/billing");
+        let n2 = router.lookup("/billing/invoices/123");
+        let n3 = router.lookup("/billing/status");
+
+        assert_eq!(n1, Some((&1, vec![])));
+        assert_eq!(n2, Some((&2, vec![("id", (18, 21))])));
+        assert_eq!(n3, Some((&3, vec![])));
+    }
+
+    #[test]
+    fn mounted_conflicts() {
+        let create = |value| {
+            let mut router: Router<usize> = Router::new(vec![Box::new(StaticParser)]);
+            router.insert("/a/b", value);
+            router
+        };
+
+        let mut router = create(1);
+
+        let error = router.mount("/", create(2), Conflict::Error).unwrap_err();
+        assert_eq!(error.path(), "/a/b");
+        assert_eq!(router.lookup("/a/b"), Some((&1, vec![])));
+
+        router.mount("/", create(2), Conflict::Keep).unwrap();
+        assert_eq!(router.lookup("/a/b"), Some((&1, vec![])));
+
+        router.mount("/", create(2), Conflict::Overwrite).unwrap();
+        assert_eq!(router.lookup("/a/b"), Some((&2, vec![])));
+
+        let mut hosts: Router<usize> =
+            Router::with_delimiter(vec![Box::new(StaticParser)], '.', true);
+        hosts.insert("example.com", 3);
+
+        let error = router.mount("/a", hosts, Conflict::Overwrite).unwrap_err();
+        assert!(error.is_incompatible());
+        assert_eq!(error.to_string(), "incompatible delimiter at path: /a");
+        assert_eq!(router.routes().len(), 1);
+    }
+
+    #[test]
+    fn mapped_routing() {
+        let mut router: Router<usize> =
+            Router::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+
+        router.insert("/1", 1);
+        router.insert("/2/:id", 2);
+
+        let cloned = router.clone();
+        let strings = router.map_ref(|v| v.to_string());
+        let doubled = router.map(|v| v * 2);
+
+        assert_eq!(cloned.lookup("/1"), Some((&1, vec![])));
+        assert_eq!(strings.lookup("/1"), Some((&"1".to_owned(), vec![])));
+        assert_eq!(doubled.lookup("/2/a"), Some((&4, vec![("id", (3, 4))])));
+
+        let failed = doubled
+            .clone()
+            .try_map(|v| if v > 2 { Err(v) } else { Ok(v) });
+        let passed = doubled.try_map(|v| Ok::<_, ()>(v + 1));
+
+        assert!(failed.is_err());
+        assert_eq!(passed.unwrap().lookup("/1"), Some((&3, vec![])));
+    }
+
+    #[test]
+    fn inherited_routing() {
+        let mut router: Router<&str> =
+            Router::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+
+        router.insert("/", "root");
+        router.insert("/admin", "admin");
+        router.insert("/admin/users/:id", "user");
+
+        let n1 = router.lookup_inherited("/admin/users/1");
+        let n2 = router.lookup_inherited("/admin");
+        let n3 = router.lookup_inherited("/admin/users");
+
+        assert_eq!(
+            n1,
+            Some((vec![&"root", &"admin", &"user"], vec![("id", (13, 14))]))
+        );
+        assert_eq!(n2, Some((vec![&"root", &"admin"], vec![])));
+        assert_eq!(n3, None);
+    }
+
+    #[test]
+    fn prefix_routing() {
+        let mut router: Router<usize> =
+            Router::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+
+        router.insert("/files", 1);
+        router.insert("/users/:id", 2);
+
+        let path = "/files/a/b/c";
+        let (value, captures, offset) = router.lookup_prefix(path).unwrap();
+
+        assert_eq!(value, &1);
+        assert_eq!(captures, vec![]);
+        assert_eq!(&path[offset..], "a/b/c");
+
+        let path = "/users/123/posts";
+        let (value, captures, offset) = router.lookup_prefix(path).unwrap();
+
+        assert_eq!(value, &2);
+        assert_eq!(captures, vec![("id", (7, 10))]);
+        assert_eq!(&path[offset..], "posts");
+
+        assert_eq!(router.lookup_prefix("/files"), Some((&1, vec![], 6)));
+        assert_eq!(router.lookup_prefix("/other"), None);
+    }
+
+    #[test]
+    fn delimited_routing() {
+        let mut router: Router<usize> = Router::with_delimiter(
+            vec![Box::new(DynamicParser), Box::new(StaticParser)],
+            '.',
+            true,
+        );
+
+        router.insert("example.com", 1);
+        router.insert(":sub.example.com", 2);
+
+        let n1 = router.lookup("example.com");
+        let n2 = router.lookup("api.example.com");
+        let n3 = router.lookup_prefix("a.b.example.com");
+
+        assert_eq!(n1, Some((&1, vec![])));
+        assert_eq!(n2, Some((&2, vec![("sub", (0, 3))])));
+        assert_eq!(n3, Some((&2, vec![("sub", (2, 3))], 1)));
+    }
+
+    #[test]
+    fn matched_routing() {
+        let mut billing: Router<usize> =
+            Router::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+
+        billing.insert("/invoices/:id", 1);
+
+        let mut router: Router<usize> =
+            Router::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+
+        router.insert("/", 2);
+        router.mount("/billing", billing, Conflict::Error).unwrap();
+
+        let n1 = router.matched_route("/billing/invoices/123");
+        let n2 = router.matched_route("/billing/invoices");
+        let n3 = router.matched_route("/");
+
+        assert_eq!(n1, RoutePattern::parse("/billing/invoices/:id").ok());
+        assert_eq!(n2, None);
+        assert_eq!(n3, RoutePattern::parse("/").ok());
+
+        let mut router: Router<usize> = Router::with_delimiter(
+            vec![Box::new(DynamicParser), Box::new(StaticParser)],
+            '.',
+            true,
+        );
+
+        router.insert(":sub.example.com", 1);
+
+        let route = router.matched_route("api.example.com");
+        let route = route.unwrap();
+        assert_eq!(route.as_str(), ":sub.example.com");
+        assert_eq!(route.params().collect::<Vec<_>>(), vec!["sub"]);
+    }
+
+    #[test]
+    fn limited_routing() {
+        let mut router: Router<usize> =
+            Router::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+
+        router.insert("/a/b/c/d", 1);
+        router.insert("/users/:id/posts/:post", 2);
+        router.insert("/v", 3);
+        router.insert("/w", 3);
+        router.insert("/x", 3);
+        router.insert("/y", 3);
+        router.insert("/z", 3);
+
+        router.limits(
+            Limits::new()
+                .max_path_bytes(32)
+                .max_segments(4)
+                .max_captures(1)
+                .max_steps(6)
+                .max_depth(4),
+        );
+
+        let long = format!("/{}", "a".repeat(32));
+
+        assert_eq!(router.try_lookup(&long), Err(LimitError::PathBytes(32)));
+        assert_eq!(
+            router.try_lookup("/a/b/c/d/e"),
+            Err(LimitError::Segments(4))
+        );
+        assert_eq!(
+            router.try_lookup("/users/1/posts/2"),
+            Err(LimitError::Captures(1))
+        );
+        assert_eq!(router.try_lookup("/z"), Err(LimitError::Steps(6)));
+        assert_eq!(router.try_lookup("/a/b/c/d"), Ok(Some((&1, vec![]))));
+        assert_eq!(router.try_lookup("/a/b/c/e"), Ok(None));
+
+        assert_eq!(router.lookup("/a/b/c/d/e"), None);
+        assert_eq!(router.lookup("/a/b/c/d"), Some((&1, vec![])));
+        assert_eq!(router.matched_route("/users/1/posts/2"), None);
+
+        assert_eq!(
+            router.try_insert("/a/b/c/d/e", 6),
+            Err(LimitError::Depth(4))
+        );
+        assert_eq!(router.try_insert("//a/b/c/e//", 6), Ok(()));
+        assert_eq!(router.lookup("/a/b/c/e"), Some((&6, vec![])));
+    }
+
+    #[test]
+    fn limited_mounting() {
+        let mut nested: Router<usize> = Router::new(vec![Box::new(StaticParser)]);
+        nested.insert("/b", 1);
+        nested.insert("/b/c", 2);
+
+        let mut router: Router<usize> = Router::new(vec![Box::new(StaticParser)]);
+        router.limits(Limits::new().max_depth(3));
+
+        let error = router
+            .mount("/x/y", nested.clone(), Conflict::Overwrite)
+            .unwrap_err();
+
+        assert_eq!(error.path(), "/x/y/b/c");
+        assert_eq!(error.limit(), Some(LimitError::Depth(3)));
+        assert_eq!(
+            error.to_string(),
+            "route exceeds a depth of 3 at path: /x/y/b/c"
+        );
+        assert_eq!(router.routes().len(), 0);
+
+        router.mount("/x", nested, Conflict::Error).unwrap();
+        assert_eq!(router.lookup("/x/b/c"), Some((&2, vec![])));
+    }
 }
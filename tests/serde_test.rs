@@ -0,0 +1,91 @@
+pub mod serde {
+    use serde::Deserialize;
+    use usher::capture::Params;
+    use usher::prelude::*;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct UserPath {
+        org: String,
+        id: u64,
+        tab: Option<String>,
+    }
+
+    #[test]
+    fn deserializing_structs() {
+        let mut router: Router<usize> =
+            Router::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+
+        router.insert("/:org/users/:id", 1);
+        router.insert("/:org/users/:id/:tab", 2);
+
+        let path = "/usher/users/123";
+        let (_, captures) = router.lookup(path).unwrap();
+        let params = Params::from_captures(path, &captures);
+        let user: UserPath = params.deserialize().unwrap();
+
+        assert_eq!(
+            user,
+            UserPath {
+                org: "usher".to_owned(),
+                id: 123,
+                tab: None,
+            }
+        );
+
+        let path = "/usher/users/123/repos";
+        let (_, captures) = router.lookup(path).unwrap();
+        let params = Params::from_captures(path, &captures);
+        let user: UserPath = params.deserialize().unwrap();
+
+        assert_eq!(user.tab.as_deref(), Some("repos"));
+
+        let path = "/usher/users/abc";
+        let (_, captures) = router.lookup(path).unwrap();
+        let params = Params::from_captures(path, &captures);
+        let error = params.deserialize::<UserPath>().unwrap_err();
+
+        assert_eq!(error.field(), Some("id"));
+        assert!(error.to_string().starts_with("invalid parameter `id`"));
+
+        let mut params = Params::new();
+        params.push("id", "123");
+
+        let error = params.deserialize::<UserPath>().unwrap_err();
+
+        assert_eq!(error.to_string(), "missing field `org`");
+    }
+
+    #[test]
+    fn deserializing_values() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct FilePath {
+            path: Vec<String>,
+        }
+
+        let mut params = Params::new();
+        params.push("path", "assets/css/main.css");
+
+        let file: FilePath = params.deserialize().unwrap();
+        assert_eq!(file.path, vec!["assets", "css", "main.css"]);
+
+        let mut params = Params::new();
+        params.push("path", "assets");
+        params.push("path", "main.css");
+
+        let file: FilePath = params.deserialize().unwrap();
+        assert_eq!(file.path, vec!["assets", "main.css"]);
+
+        let mut params = Params::new();
+        params.push("org", "usher");
+        params.push("id", "123");
+
+        let (org, id): (String, u32) = params.deserialize().unwrap();
+        assert_eq!((org.as_str(), id), ("usher", 123));
+
+        let mut params = Params::new();
+        params.push("id", "123");
+
+        let id: u64 = params.deserialize().unwrap();
+        assert_eq!(id, 123);
+    }
+}
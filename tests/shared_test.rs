@@ -0,0 +1,29 @@
+pub mod shared {
+    use usher::prelude::*;
+    use usher::shared::SharedRouter;
+
+    #[test]
+    fn swapped_routing() {
+        let mut router: Router<usize> = Router::new(vec![Box::new(StaticParser)]);
+        router.insert("/1", 1);
+
+        let shared = SharedRouter::new(router);
+        let handle = shared.clone();
+        let snapshot = handle.load_full();
+
+        shared.update(|current| {
+            let mut router = current.clone();
+            router.insert("/2", 2);
+            router
+        });
+
+        assert_eq!(snapshot.lookup("/2"), None);
+        assert_eq!(handle.load().lookup("/1"), Some((&1, vec![])));
+        assert_eq!(handle.load().lookup("/2"), Some((&2, vec![])));
+
+        let previous = handle.swap(Router::new(vec![Box::new(StaticParser)]));
+
+        assert_eq!(previous.lookup("/2"), Some((&2, vec![])));
+        assert_eq!(shared.load().lookup("/1"), None);
+    }
+}
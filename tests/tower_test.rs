@@ -0,0 +1,91 @@
+pub mod tower {
+    use http::{Request, Response, StatusCode};
+    use tower::util::{service_fn, BoxCloneService};
+    use tower::ServiceExt;
+    use usher::http::{HttpRouter, RequestExt};
+    use usher::normalize::Normalizer;
+    use usher::prelude::*;
+
+    use std::convert::Infallible;
+
+    type Svc = BoxCloneService<Request<()>, Response<String>, Infallible>;
+
+    fn echo(prefix: &'static str) -> Svc {
+        BoxCloneService::new(service_fn(move |req: Request<()>| async move {
+            let name = req.param("name").unwrap_or("nobody");
+            let route = req.matched_route().unwrap_or("-");
+            Ok(Response::new(format!("{}: {} ({})", prefix, name, route)))
+        }))
+    }
+
+    #[tokio::test]
+    async fn service_routing() {
+        let mut router: HttpRouter<Svc> =
+            HttpRouter::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+
+        router.get("/hello/:name", echo("hello"));
+        router.auto_head(true);
+
+        let req = |method: &str, path: &str| {
+            Request::builder()
+                .method(method)
+                .uri(path)
+                .body(())
+                .unwrap()
+        };
+
+        let res = router.clone().oneshot(req("GET", "/hello/usher")).await;
+        assert_eq!(res.unwrap().into_body(), "hello: usher (/hello/:name)");
+
+        let res = router.clone().oneshot(req("HEAD", "/hello/usher")).await;
+        assert_eq!(res.unwrap().into_body(), "");
+
+        let res = router.clone().oneshot(req("POST", "/hello/usher")).await;
+        let res = res.unwrap();
+        assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(res.headers()["allow"], "GET, HEAD");
+
+        let res = router.clone().oneshot(req("GET", "/missing")).await;
+        assert_eq!(res.unwrap().status(), StatusCode::NOT_FOUND);
+
+        router.fallback(echo("fallback"));
+
+        let res = router.oneshot(req("GET", "/missing")).await;
+        assert_eq!(res.unwrap().into_body(), "fallback: nobody (-)");
+    }
+
+    #[tokio::test]
+    async fn normalized_service_routing() {
+        let mut router: HttpRouter<Svc> =
+            HttpRouter::new(vec![Box::new(DynamicParser), Box::new(StaticParser)]);
+
+        router.get("/static/:name/:file", echo("static"));
+        router.normalize(Normalizer::new());
+
+        let req = |path: &str| Request::get(path).body(()).unwrap();
+
+        let res = router.clone().oneshot(req("/static/css/a.css")).await;
+        assert_eq!(
+            res.unwrap().into_body(),
+            "static: css (/static/:name/:file)"
+        );
+
+        let res = router.clone().oneshot(req("/static/../admin")).await;
+        assert_eq!(res.unwrap().status(), StatusCode::NOT_FOUND);
+
+        router.auto_options(true);
+
+        let res = router
+            .clone()
+            .oneshot(Request::options("*").body(()).unwrap())
+            .await;
+        let res = res.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers()["allow"], "GET, OPTIONS");
+
+        router.fallback(echo("fallback"));
+
+        let res = router.oneshot(req("/static/../admin")).await;
+        assert_eq!(res.unwrap().into_body(), "fallback: nobody (-)");
+    }
+}
@@ -0,0 +1,172 @@
+//! Procedural macros for the `usher` crate.
+//!
+//! This crate should not be used directly; the macros are re-exported by the
+//! `usher` crate when the `"macros"` Cargo feature is enabled.
+extern crate proc_macro;
+
+mod pattern;
+mod route;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{parse_macro_input, DeriveInput, Expr, Ident, LitStr, Token};
+
+use crate::pattern::Tree;
+
+/// Constructs a router from a set of routes, validated at compile time.
+///
+/// See the documentation of the `usher` crate for details.
+#[proc_macro]
+pub fn router(input: TokenStream) -> TokenStream {
+    let routes = parse_macro_input!(input as Routes);
+    match expand(routes) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// Derives parsing and formatting of paths for an enum of routes.
+///
+/// See the documentation of the `usher` crate for details.
+#[proc_macro_derive(Route, attributes(route))]
+pub fn derive_route(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match route::expand(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// A set of routes provided to the `router!` macro.
+struct Routes {
+    entries: Punctuated<Entry, Token![,]>,
+}
+
+impl Parse for Routes {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(Self {
+            entries: Punctuated::parse_terminated(input)?,
+        })
+    }
+}
+
+/// A single route provided to the `router!` macro.
+struct Entry {
+    method: Option<Ident>,
+    path: LitStr,
+    value: Expr,
+}
+
+impl Parse for Entry {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let method = if input.peek(Ident) {
+            Some(input.parse()?)
+        } else {
+            None
+        };
+
+        let path = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        let value = input.parse()?;
+
+        Ok(Self {
+            method,
+            path,
+            value,
+        })
+    }
+}
+
+/// Validates a set of routes and expands them into router construction.
+fn expand(routes: Routes) -> syn::Result<TokenStream2> {
+    let http = routes
+        .entries
+        .first()
+        .is_some_and(|entry| entry.method.is_some());
+
+    let mut tree = Tree::default();
+    let mut inserts = Vec::new();
+
+    // locals of the expansion must not be visible to the expressions of routes
+    let router = Ident::new("router", Span::mixed_site());
+    let parsers = Ident::new("parsers", Span::mixed_site());
+
+    for entry in routes.entries {
+        let Entry {
+            method,
+            path,
+            value,
+        } = entry;
+
+        let method = match (http, method) {
+            (true, Some(method)) => Some(method),
+            (false, None) => None,
+            _ => {
+                return Err(syn::Error::new(
+                    path.span(),
+                    "either all routes or no routes must have a method",
+                ))
+            }
+        };
+
+        let error = |msg| syn::Error::new(path.span(), msg);
+        let segments = pattern::parse(&path.value()).map_err(error)?;
+
+        let name = method.as_ref().map(Ident::to_string);
+        tree.insert(&segments, name.as_deref()).map_err(error)?;
+
+        let insert = match method {
+            None => quote! { #router.insert(#path, #value); },
+            Some(method) if method == "ANY" => quote! { #router.any(#path, #value); },
+            Some(method) => {
+                let method = expand_method(&method)?;
+                quote! { #router.route(#method, #path, #value); }
+            }
+        };
+
+        inserts.push(insert);
+    }
+
+    let constructor = if http {
+        quote! { ::usher::http::HttpRouter::new }
+    } else {
+        quote! { ::usher::router::Router::new }
+    };
+
+    Ok(quote! {
+        {
+            let #parsers: ::std::vec::Vec<::std::boxed::Box<dyn ::usher::parser::Parser>> = ::std::vec![
+                ::std::boxed::Box::new(::usher::parser::DynamicParser),
+                ::std::boxed::Box::new(::usher::parser::StaticParser),
+            ];
+            let mut #router = #constructor(#parsers);
+            #(#inserts)*
+            #router
+        }
+    })
+}
+
+/// Expands a method identifier into an expression creating the `Method`.
+fn expand_method(method: &Ident) -> syn::Result<TokenStream2> {
+    let name = method.to_string();
+
+    if !name.bytes().all(|b| b.is_ascii_uppercase() || b == b'_') {
+        return Err(syn::Error::new(
+            method.span(),
+            format!("invalid method `{}`: methods must be uppercase", name),
+        ));
+    }
+
+    Ok(match name.as_str() {
+        "CONNECT" | "DELETE" | "GET" | "HEAD" | "OPTIONS" | "PATCH" | "POST" | "PUT" | "TRACE" => {
+            quote! { ::usher::__private::Method::#method }
+        }
+        _ => {
+            let bytes = syn::LitByteStr::new(name.as_bytes(), method.span());
+            quote! { ::usher::__private::Method::from_bytes(#bytes).expect("invalid method") }
+        }
+    })
+}
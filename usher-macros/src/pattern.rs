@@ -0,0 +1,113 @@
+//! Validation of route patterns at compile time.
+//!
+//! Patterns are parsed using the grammar shared with the `RoutePattern` type
+//! of the `usher` crate, which is given meaning by the `DynamicParser` and the
+//! `StaticParser` used by the generated routers. Routes are inserted into a
+//! model of the routing tree using the same rules as the runtime router, so
+//! that any route which would be shadowed by (or would overwrite) another route
+//! can be reported as an error. As parameters and wildcards match any segment,
+//! any segment registered after one in the same position would never be reached
+//! and is also reported (as is a literal matching an earlier constraint).
+pub use usher_pattern::Segment;
+
+/// Parses a route pattern into segments, validating the syntax.
+pub fn parse(path: &str) -> Result<Vec<Segment>, String> {
+    usher_pattern::parse(path).map_err(|err| format!("invalid route `{}`: {}", path, err))
+}
+
+/// Checks whether an incoming segment would be merged into an existing segment.
+fn matches(existing: &Segment, incoming: &Segment) -> bool {
+    if existing == incoming {
+        return true;
+    }
+
+    match (existing, incoming) {
+        (Segment::Wildcard(_), _) => false,
+        (_, Segment::Literal(literal)) => existing.is_match(literal),
+        (Segment::Parameter(_), Segment::Parameter(_)) => true,
+        _ => false,
+    }
+}
+
+/// A model of a routing tree, used to detect conflicts between routes.
+#[derive(Default)]
+pub struct Tree {
+    root: Node,
+}
+
+/// A node within a `Tree`, with the methods registered against it.
+#[derive(Default)]
+struct Node {
+    children: Vec<(Segment, Node)>,
+    methods: Vec<Option<String>>,
+}
+
+impl Tree {
+    /// Inserts a route into the tree, failing if it conflicts with another route.
+    pub fn insert(&mut self, segments: &[Segment], method: Option<&str>) -> Result<(), String> {
+        let mut current = &mut self.root;
+        let mut walked = String::new();
+
+        for segment in segments {
+            let idx = current
+                .children
+                .iter()
+                .position(|(existing, _)| matches(existing, segment));
+
+            let idx = match idx {
+                Some(idx) => idx,
+                None => {
+                    // parameters and wildcards catch every segment before this one
+                    let shadow = current.children.iter().find(|(existing, _)| {
+                        matches!(existing, Segment::Parameter(_) | Segment::Wildcard(_))
+                    });
+
+                    if let Some((existing, _)) = shadow {
+                        return Err(format!(
+                            "segment `{}` is shadowed by `{}` in the route `{}/{}`",
+                            segment, existing, walked, existing
+                        ));
+                    }
+
+                    current.children.push((segment.clone(), Node::default()));
+                    current.children.len() - 1
+                }
+            };
+
+            let (existing, child) = &mut current.children[idx];
+
+            walked.push('/');
+            walked.push_str(&existing.to_string());
+
+            match (&*existing, segment) {
+                (a, b) if a == b => (),
+                (Segment::Parameter(a), Segment::Parameter(b)) => {
+                    return Err(format!(
+                        "parameter `:{}` conflicts with `:{}` in the route `{}`",
+                        b, a, walked
+                    ));
+                }
+                (a, b) => {
+                    return Err(format!(
+                        "segment `{}` is shadowed by `{}` in the route `{}`",
+                        b, a, walked
+                    ));
+                }
+            }
+
+            current = child;
+        }
+
+        let method = method.map(str::to_owned);
+        if current.methods.contains(&method) {
+            if walked.is_empty() {
+                walked.push('/');
+            }
+            let prefix = method.map(|m| format!("{} ", m)).unwrap_or_default();
+            return Err(format!("duplicate route `{}{}`", prefix, walked));
+        }
+
+        current.methods.push(method);
+        Ok(())
+    }
+}
@@ -0,0 +1,194 @@
+//! Implementation of the `Route` derive for enums.
+//!
+//! Each variant of the enum is annotated with a route pattern, which is used
+//! to generate both a `Router` to parse paths into the enum and a `Display`
+//! implementation to format the enum back into a path.
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Fields, LitStr};
+
+use crate::pattern::{self, Segment, Tree};
+
+/// Expands the `Route` derive for the provided input.
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.span(),
+            "routes cannot be derived for generic types",
+        ));
+    }
+
+    let variants = match &input.data {
+        Data::Enum(data) => &data.variants,
+        _ => {
+            return Err(syn::Error::new(
+                input.span(),
+                "routes can only be derived for enums",
+            ))
+        }
+    };
+
+    let mut tree = Tree::default();
+    let mut inserts = Vec::new();
+    let mut formats = Vec::new();
+
+    for variant in variants {
+        let ident = &variant.ident;
+
+        let attr = variant
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("route"))
+            .ok_or_else(|| {
+                syn::Error::new(variant.span(), "missing #[route(\"...\")] attribute")
+            })?;
+
+        let path: LitStr = attr.parse_args()?;
+        let error = |msg| syn::Error::new(path.span(), msg);
+
+        let segments = pattern::parse(&path.value()).map_err(error)?;
+        tree.insert(&segments, None).map_err(error)?;
+
+        let fields: Vec<_> = match &variant.fields {
+            Fields::Unit => Vec::new(),
+            Fields::Named(fields) => fields
+                .named
+                .iter()
+                .filter_map(|f| f.ident.as_ref())
+                .collect(),
+            Fields::Unnamed(fields) => {
+                return Err(syn::Error::new(
+                    fields.span(),
+                    "tuple variants are not supported; use named fields",
+                ))
+            }
+        };
+
+        let params: Vec<&str> = segments.iter().filter_map(Segment::name).collect();
+
+        for param in &params {
+            if !fields.iter().any(|field| field == param) {
+                return Err(error(format!(
+                    "parameter `:{}` has no matching field",
+                    param
+                )));
+            }
+        }
+
+        for field in &fields {
+            if !params.iter().any(|param| field == param) {
+                return Err(syn::Error::new(
+                    field.span(),
+                    format!("field `{}` does not appear in the route", field),
+                ));
+            }
+        }
+
+        // construction of the variant from the captures of a path
+        let construct = if fields.is_empty() {
+            quote! { #name::#ident }
+        } else {
+            let values = fields.iter().map(|field| {
+                let param = field.to_string();
+                quote! {
+                    #field: {
+                        let __value = ::usher::capture::find_capture(__path, __captures, #param)?;
+                        ::usher::normalize::decode_segment(__value)?.parse().ok()?
+                    }
+                }
+            });
+            quote! { #name::#ident { #(#values),* } }
+        };
+
+        inserts.push(quote! {
+            router.insert(#path, |__path, __captures| ::std::option::Option::Some(#construct));
+        });
+
+        // formatting of the variant back into a path
+        let writes = segments.iter().map(|segment| match segment {
+            Segment::Literal(literal) => {
+                let literal = format!("/{}", literal);
+                quote! { __f.write_str(#literal)?; }
+            }
+            Segment::Parameter(field) | Segment::Constrained { name: field, .. } => {
+                let field = syn::Ident::new(field, path.span());
+                quote! {
+                    let __value = ::std::string::ToString::to_string(#field);
+                    __f.write_str("/")?;
+                    __f.write_str(&::usher::normalize::encode_segment(&__value))?;
+                }
+            }
+            Segment::Wildcard(field) => {
+                let field = syn::Ident::new(field, path.span());
+                quote! {
+                    let __value = ::std::string::ToString::to_string(#field);
+                    for __part in __value.split('/') {
+                        __f.write_str("/")?;
+                        __f.write_str(&::usher::normalize::encode_segment(__part))?;
+                    }
+                }
+            }
+        });
+
+        let root = if segments.is_empty() {
+            quote! { __f.write_str("/")?; }
+        } else {
+            quote! {}
+        };
+
+        let pattern = if fields.is_empty() {
+            quote! { #name::#ident }
+        } else {
+            quote! { #name::#ident { #(#fields),* } }
+        };
+
+        formats.push(quote! {
+            #pattern => {
+                #root
+                #(#writes)*
+            }
+        });
+    }
+
+    let parse_fn = quote! {
+        fn(&str, ::usher::capture::CapturesRef) -> ::std::option::Option<#name>
+    };
+
+    Ok(quote! {
+        impl #name {
+            /// Creates a `Router` which parses paths into this type.
+            pub fn router() -> ::usher::router::Router<#parse_fn> {
+                let parsers: ::std::vec::Vec<::std::boxed::Box<dyn ::usher::parser::Parser>> = ::std::vec![
+                    ::std::boxed::Box::new(::usher::parser::DynamicParser),
+                    ::std::boxed::Box::new(::usher::parser::StaticParser),
+                ];
+                let mut router: ::usher::router::Router<#parse_fn> = ::usher::router::Router::new(parsers);
+                #(#inserts)*
+                router
+            }
+
+            /// Attempts to parse a path into this type.
+            pub fn from_path(path: &str) -> ::std::option::Option<Self> {
+                static ROUTER: ::std::sync::OnceLock<::usher::router::Router<#parse_fn>> =
+                    ::std::sync::OnceLock::new();
+
+                let router = ROUTER.get_or_init(Self::router);
+                let (parse, captures) = router.lookup(path)?;
+
+                parse(path, &captures)
+            }
+        }
+
+        impl ::std::fmt::Display for #name {
+            fn fmt(&self, __f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                match self {
+                    #(#formats)*
+                }
+                ::std::result::Result::Ok(())
+            }
+        }
+    })
+}
@@ -0,0 +1,224 @@
+//! Route pattern grammar shared by the `usher` crates.
+//!
+//! This crate should not be used directly; the types are re-exported by the
+//! `usher` crate via the `pattern` module, and the same parser is used by the
+//! `usher-macros` crate to validate patterns during compilation.
+//!
+//! The syntax of each segment of a pattern is as follows:
+//!
+//! - `:name` is a parameter, capturing any value.
+//! - `:name(constraint)` is a parameter which only captures values matching the
+//!   constraint, which is a regular expression matched against the entire value.
+//! - `*name` is a wildcard, capturing the rest of the path. It may only appear as
+//!   the final segment of a pattern.
+//! - Anything else is a literal. A literal which would otherwise start with `:`,
+//!   `*` or `\` is escaped with a leading `\` (i.e. `\:name` is the literal `:name`).
+//!
+//! Empty segments are ignored, as they are by a router. As the pattern is split
+//! into segments before parsing, a constraint cannot contain a `/` character.
+use regex_lite::Regex;
+
+use std::error::Error;
+use std::fmt;
+
+/// A single segment of a route pattern.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Segment {
+    /// A literal segment, which has already been unescaped.
+    Literal(String),
+    /// A parameter segment, such as `:id`.
+    Parameter(String),
+    /// A parameter segment with a constraint, such as `:id(\d+)`.
+    Constrained {
+        /// The name of the parameter.
+        name: String,
+        /// The constraint of the parameter.
+        constraint: String,
+    },
+    /// A wildcard segment, such as `*path`.
+    Wildcard(String),
+}
+
+impl Segment {
+    /// Parses a single segment of a route pattern, validating the syntax.
+    pub fn parse(raw: &str) -> Result<Self, PatternError> {
+        if let Some(literal) = unescape(raw) {
+            return Ok(Segment::Literal(literal.to_owned()));
+        }
+
+        if let Some(name) = raw.strip_prefix('*') {
+            if name.is_empty() {
+                return Err(PatternError::MissingName);
+            }
+            return Ok(Segment::Wildcard(name.to_owned()));
+        }
+
+        let rest = match raw.strip_prefix(':') {
+            Some(rest) => rest,
+            None => return Ok(Segment::Literal(raw.to_owned())),
+        };
+
+        let (name, constraint) = match rest.find('(') {
+            None => (rest, None),
+            Some(idx) => {
+                let constraint = rest[idx + 1..]
+                    .strip_suffix(')')
+                    .filter(|constraint| Constraint::new(constraint).is_some())
+                    .ok_or_else(|| PatternError::InvalidConstraint(raw.to_owned()))?;
+                (&rest[..idx], Some(constraint))
+            }
+        };
+
+        if name.is_empty() {
+            return Err(PatternError::MissingName);
+        }
+
+        if name.contains(')') {
+            return Err(PatternError::InvalidConstraint(raw.to_owned()));
+        }
+
+        Ok(match constraint {
+            None => Segment::Parameter(name.to_owned()),
+            Some(constraint) => Segment::Constrained {
+                name: name.to_owned(),
+                constraint: constraint.to_owned(),
+            },
+        })
+    }
+
+    /// Retrieves the name of the parameter for this segment, if any.
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Segment::Literal(_) => None,
+            Segment::Parameter(name) => Some(name),
+            Segment::Constrained { name, .. } => Some(name),
+            Segment::Wildcard(name) => Some(name),
+        }
+    }
+
+    /// Determines whether a value would be matched by this segment.
+    ///
+    /// A wildcard matches any value, which may span several segments of a path,
+    /// whereas all other segments expect the value of a single segment.
+    pub fn is_match(&self, value: &str) -> bool {
+        match self {
+            Segment::Literal(literal) => literal == value,
+            Segment::Parameter(_) | Segment::Wildcard(_) => true,
+            Segment::Constrained { constraint, .. } => {
+                Constraint::new(constraint).is_some_and(|constraint| constraint.is_match(value))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Segment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Segment::Parameter(name) => write!(f, ":{}", name),
+            Segment::Constrained { name, constraint } => write!(f, ":{}({})", name, constraint),
+            Segment::Wildcard(name) => write!(f, "*{}", name),
+            Segment::Literal(literal) => {
+                let escaped = format!("\\{}", literal);
+                if unescape(&escaped).is_some() {
+                    f.write_str(&escaped)
+                } else {
+                    f.write_str(literal)
+                }
+            }
+        }
+    }
+}
+
+/// A compiled constraint of a constrained parameter.
+///
+/// Constraints are regular expressions, which must match the entire value of
+/// a segment (rather than any substring of the segment).
+#[derive(Clone, Debug)]
+pub struct Constraint {
+    regex: Regex,
+}
+
+impl Constraint {
+    /// Compiles a constraint, returning `None` if the constraint is invalid.
+    pub fn new(constraint: &str) -> Option<Self> {
+        if constraint.is_empty() {
+            return None;
+        }
+
+        let regex = Regex::new(&format!("^(?:{})$", constraint)).ok()?;
+
+        Some(Self { regex })
+    }
+
+    /// Determines whether a value satisfies this constraint.
+    pub fn is_match(&self, value: &str) -> bool {
+        self.regex.is_match(value)
+    }
+}
+
+/// Error type returned when a route pattern is invalid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PatternError {
+    /// The pattern does not start with a `/`.
+    MissingSlash,
+    /// A parameter or wildcard has no name.
+    MissingName,
+    /// A parameter name is used more than once.
+    DuplicateName(String),
+    /// A constraint is empty, not closed, or not a valid regular expression.
+    InvalidConstraint(String),
+    /// A wildcard is followed by further segments.
+    MisplacedWildcard(String),
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PatternError::MissingSlash => write!(f, "pattern must start with `/`"),
+            PatternError::MissingName => write!(f, "parameter has no name"),
+            PatternError::DuplicateName(name) => write!(f, "parameter `:{}` is used twice", name),
+            PatternError::InvalidConstraint(seg) => write!(f, "invalid constraint in `{}`", seg),
+            PatternError::MisplacedWildcard(name) => {
+                write!(f, "wildcard `*{}` must be the final segment", name)
+            }
+        }
+    }
+}
+
+impl Error for PatternError {}
+
+/// Parses a route pattern into segments, validating the syntax.
+pub fn parse(pattern: &str) -> Result<Vec<Segment>, PatternError> {
+    let rest = pattern
+        .strip_prefix('/')
+        .ok_or(PatternError::MissingSlash)?;
+
+    let mut segments: Vec<Segment> = Vec::new();
+
+    for raw in rest.split('/').filter(|raw| !raw.is_empty()) {
+        if let Some(Segment::Wildcard(name)) = segments.last() {
+            return Err(PatternError::MisplacedWildcard(name.clone()));
+        }
+
+        let segment = Segment::parse(raw)?;
+
+        if let Some(name) = segment.name() {
+            if segments.iter().any(|s| s.name() == Some(name)) {
+                return Err(PatternError::DuplicateName(name.to_owned()));
+            }
+        }
+
+        segments.push(segment);
+    }
+
+    Ok(segments)
+}
+
+/// Removes the escape from an escaped literal segment, if it is one.
+///
+/// Only a leading `\` followed by `:`, `*` or `\` is treated as an escape, so
+/// that any other use of `\` within a literal is left untouched.
+fn unescape(raw: &str) -> Option<&str> {
+    raw.strip_prefix('\\')
+        .filter(|rest| rest.starts_with([':', '*', '\\']))
+}